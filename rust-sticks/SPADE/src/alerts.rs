@@ -0,0 +1,527 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Result};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::decoder::DecoderRegistry;
+use crate::filters;
+use crate::layout::Field;
+use crate::snapshot::SnapshotAccount;
+use crate::watch::{self, Change, LiveSnapshot};
+use crate::AccountFetcher;
+
+/// Condition that fires a rule
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleKind {
+    /// An integer field crosses `below` or `above`
+    Threshold,
+    /// The named `field`, or the bytes at `offset..offset + len`, change
+    FieldChanged,
+    /// A new account is created
+    Created,
+    /// An account is closed
+    Closed,
+}
+
+/// Integer types a threshold rule can read
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueType {
+    U8,
+    U16,
+    U32,
+    #[default]
+    U64,
+    I64,
+}
+
+impl ValueType {
+    /// The unsigned type of a decoded field's width
+    fn of_len(len: usize) -> Option<Self> {
+        match len {
+            1 => Some(ValueType::U8),
+            2 => Some(ValueType::U16),
+            4 => Some(ValueType::U32),
+            8 => Some(ValueType::U64),
+            _ => None,
+        }
+    }
+
+    fn width(self) -> usize {
+        match self {
+            ValueType::U8 => 1,
+            ValueType::U16 => 2,
+            ValueType::U32 => 4,
+            ValueType::U64 | ValueType::I64 => 8,
+        }
+    }
+
+    /// Reads the value at `offset`, little-endian
+    fn read(self, data: &[u8], offset: usize) -> Option<i128> {
+        let bytes = data.get(offset..offset + self.width())?;
+        Some(match self {
+            ValueType::U8 => bytes[0] as i128,
+            ValueType::U16 => u16::from_le_bytes(bytes.try_into().ok()?) as i128,
+            ValueType::U32 => u32::from_le_bytes(bytes.try_into().ok()?) as i128,
+            ValueType::U64 => u64::from_le_bytes(bytes.try_into().ok()?) as i128,
+            ValueType::I64 => i64::from_le_bytes(bytes.try_into().ok()?) as i128,
+        })
+    }
+}
+
+/// A threshold bound. TOML integers stop at i64::MAX, so larger u64 bounds can be given as
+/// strings, e.g. `above = "18000000000000000000"`
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "BoundValue")]
+pub struct Bound(i128);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BoundValue {
+    Signed(i64),
+    Unsigned(u64),
+    Text(String),
+}
+
+impl TryFrom<BoundValue> for Bound {
+    type Error = String;
+
+    fn try_from(value: BoundValue) -> Result<Self, String> {
+        match value {
+            BoundValue::Signed(v) => Ok(Bound(v as i128)),
+            BoundValue::Unsigned(v) => Ok(Bound(v as i128)),
+            BoundValue::Text(text) => text.trim().parse::<i128>()
+                .ok()
+                .filter(|v| (i64::MIN as i128..=u64::MAX as i128).contains(v))
+                .map(Bound)
+                .ok_or_else(|| format!("invalid threshold '{}'", text)),
+        }
+    }
+}
+
+impl fmt::Display for Bound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// One alert rule from the rules file
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    pub name: String,
+    pub kind: RuleKind,
+    /// Only accounts starting with this 8-byte discriminator (hex)
+    #[serde(default)]
+    pub discriminator: Option<String>,
+    /// Only this account
+    #[serde(default)]
+    pub account: Option<String>,
+    /// Decoded field name (IDL, built-in decoder or inferred layout), instead of `offset`/`len`
+    #[serde(default)]
+    pub field: Option<String>,
+    #[serde(default)]
+    pub offset: usize,
+    /// Field length for `field_changed`, 32 is shown as a pubkey
+    #[serde(default)]
+    pub len: Option<usize>,
+    /// Integer type for `threshold`; u64 at an offset, the field's width for a named field
+    #[serde(default, rename = "type")]
+    pub value_type: Option<ValueType>,
+    #[serde(default)]
+    pub below: Option<Bound>,
+    #[serde(default)]
+    pub above: Option<Bound>,
+}
+
+/// Where fired alerts are delivered
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Output {
+    Stdout,
+    File { path: String },
+    Webhook { url: String },
+}
+
+/// Contents of a rules file (TOML, or YAML for `.yaml`/`.yml` files)
+#[derive(Debug, Deserialize)]
+pub struct RuleSet {
+    #[serde(default, rename = "rule")]
+    pub rules: Vec<Rule>,
+    #[serde(default, rename = "output")]
+    pub outputs: Vec<Output>,
+    /// Shared by webhook outputs, created when the rules file has one
+    #[serde(skip)]
+    http: Option<reqwest::blocking::Client>,
+}
+
+impl RuleSet {
+    /// Loads and validates a rules file
+    pub fn load(path: &str) -> Result<Self> {
+        let text = fs::read_to_string(path)?;
+        let yaml = Path::new(path).extension().is_some_and(|ext| ext == "yaml" || ext == "yml");
+        let rule_set = if yaml { serde_yaml::from_str(&text)? } else { toml::from_str(&text)? };
+        Self::validate(rule_set).map_err(|e| anyhow!("{}: {}", path, e))
+    }
+
+    fn validate(mut rule_set: RuleSet) -> Result<Self> {
+        if rule_set.rules.is_empty() {
+            bail!("no rules defined");
+        }
+        if rule_set.outputs.is_empty() {
+            rule_set.outputs.push(Output::Stdout);
+        }
+        if rule_set.outputs.iter().any(|output| matches!(output, Output::Webhook { .. })) {
+            rule_set.http = Some(reqwest::blocking::Client::builder().timeout(Duration::from_secs(10)).build()?);
+        }
+
+        for rule in &rule_set.rules {
+            if let Some(disc) = &rule.discriminator {
+                if hex::decode(disc).map(|d| d.len()).unwrap_or(0) != 8 {
+                    bail!("Rule '{}': discriminator must be 8 hex bytes", rule.name);
+                }
+            }
+            if let Some(account) = &rule.account {
+                Pubkey::from_str(account).map_err(|_| anyhow!("Rule '{}': invalid account", rule.name))?;
+            }
+            if rule.kind == RuleKind::Threshold && rule.below.is_none() && rule.above.is_none() {
+                bail!("Rule '{}': threshold rules need `below` or `above`", rule.name);
+            }
+            if rule.field.is_some() && (rule.offset != 0 || rule.len.is_some()) {
+                bail!("Rule '{}': give either `field` or `offset`/`len`", rule.name);
+            }
+            if rule.kind == RuleKind::FieldChanged && rule.len.is_none() && rule.field.is_none() {
+                bail!("Rule '{}': field_changed rules need `field` or `len`", rule.name);
+            }
+        }
+
+        Ok(rule_set)
+    }
+}
+
+/// A rule that fired for an account
+#[derive(Debug)]
+pub struct Alert {
+    pub rule: String,
+    pub account: Pubkey,
+    pub slot: u64,
+    pub message: String,
+}
+
+impl Rule {
+    /// True if the rule's account and discriminator filters accept this account
+    fn applies_to(&self, account: &SnapshotAccount) -> bool {
+        let account_ok = self.account.as_deref()
+            .is_none_or(|a| a == account.pubkey.to_string());
+        let disc_ok = self.discriminator.as_deref()
+            .is_none_or(|d| hex::decode(d).map(|d| account.data.starts_with(&d)).unwrap_or(false));
+        account_ok && disc_ok
+    }
+
+    /// Describes which threshold a value violates, if any
+    fn violation(&self, value: i128) -> Option<String> {
+        match (self.below, self.above) {
+            (Some(below), _) if value < below.0 => Some(format!("below {}", below)),
+            (_, Some(above)) if value > above.0 => Some(format!("above {}", above)),
+            _ => None,
+        }
+    }
+
+    /// Formats the watched bytes for messages
+    fn format_bytes(&self, data: &[u8]) -> String {
+        let len = self.len.unwrap_or(0);
+        match data.get(self.offset..self.offset + len) {
+            Some(bytes) if len == 32 => Pubkey::try_from(bytes).map(|p| p.to_string()).unwrap_or_default(),
+            Some(bytes) => hex::encode(bytes),
+            None => "-".to_string(),
+        }
+    }
+
+    /// What the rule watches, for messages
+    fn target(&self) -> String {
+        match &self.field {
+            Some(field) => field.clone(),
+            None => format!("value at offset {}", self.offset),
+        }
+    }
+
+    /// The rule's named field in an account's layout
+    fn find_field(&self, account: &SnapshotAccount, layout: &dyn Fn(&SnapshotAccount) -> Vec<Field>) -> Option<Field> {
+        let name = self.field.as_deref()?;
+        layout(account).into_iter().find(|field| field.name == name)
+    }
+
+    /// Reads the integer the rule watches: the named field, or `type` at `offset`
+    fn read_value(&self, account: &SnapshotAccount, layout: &dyn Fn(&SnapshotAccount) -> Vec<Field>) -> Option<i128> {
+        if self.field.is_none() {
+            return self.value_type.unwrap_or_default().read(&account.data, self.offset);
+        }
+        let field = self.find_field(account, layout)?;
+        let value_type = self.value_type.or_else(|| ValueType::of_len(field.len))?;
+        if value_type.width() > field.len {
+            return None;
+        }
+        value_type.read(&account.data, field.offset)
+    }
+
+    /// Evaluates the rule against a change, returning a message if it fires. `layout` gives
+    /// the decoded or inferred fields of an account, for rules that name a field
+    pub fn evaluate(&self, change: &Change, layout: &dyn Fn(&SnapshotAccount) -> Vec<Field>) -> Option<String> {
+        match (self.kind, change) {
+            (RuleKind::Created, Change::Created(account)) if self.applies_to(account) => {
+                Some(format!("account created ({} bytes)", account.data.len()))
+            }
+            (RuleKind::Closed, Change::Closed(account)) if self.applies_to(account) => {
+                Some(format!("account closed ({} lamports reclaimed)", account.lamports))
+            }
+            (RuleKind::Threshold, Change::Created(account)) if self.applies_to(account) => {
+                let value = self.read_value(account, layout)?;
+                self.violation(value)
+                    .map(|v| format!("new account with {} {} ({})", self.target(), value, v))
+            }
+            (RuleKind::Threshold, Change::Updated(old, new)) if self.applies_to(new) => {
+                let before = self.read_value(old, layout)?;
+                let after = self.read_value(new, layout)?;
+                // Only fire when the value crosses a threshold, not on every update past it. A
+                // jump from below `below` straight to above `above` crosses both
+                match (self.violation(before), self.violation(after)) {
+                    (before_v, Some(v)) if before_v.as_ref() != Some(&v) => Some(format!(
+                        "{} went {} → {} ({})", self.target(), before, after, v)),
+                    _ => None,
+                }
+            }
+            (RuleKind::FieldChanged, Change::Updated(old, new)) if self.applies_to(new) => {
+                if self.field.is_some() {
+                    let before = self.find_field(old, layout);
+                    let after = self.find_field(new, layout);
+                    let old_bytes = before.as_ref().and_then(|f| old.data.get(f.offset..f.end()));
+                    let new_bytes = after.as_ref().and_then(|f| new.data.get(f.offset..f.end()));
+                    let shown = |field: &Option<Field>| field.as_ref().map_or("-".to_string(), |f| f.value.clone());
+                    return (old_bytes != new_bytes)
+                        .then(|| format!("{} changed: {} → {}", self.target(), shown(&before), shown(&after)));
+                }
+                let len = self.len?;
+                let before = old.data.get(self.offset..self.offset + len);
+                let after = new.data.get(self.offset..self.offset + len);
+                (before != after).then(|| format!("field at offset {} changed: {} → {}",
+                    self.offset, self.format_bytes(&old.data), self.format_bytes(&new.data)))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the account a change refers to
+fn change_account(change: &Change) -> &SnapshotAccount {
+    match change {
+        Change::Created(account) | Change::Closed(account) => account,
+        Change::Updated(_, account) => account,
+    }
+}
+
+/// Sends an alert to every configured output, reporting (not failing on) delivery errors
+pub fn deliver(rule_set: &RuleSet, program_id: &Pubkey, alert: &Alert) {
+    let timestamp = Utc::now();
+    let payload = json!({
+        "rule": alert.rule,
+        "program": program_id.to_string(),
+        "account": alert.account.to_string(),
+        "slot": alert.slot,
+        "message": alert.message,
+        "timestamp": timestamp.to_rfc3339(),
+    });
+
+    for output in &rule_set.outputs {
+        let result: Result<()> = match output {
+            Output::Stdout => {
+                println!("\n🚨 [{}] {} — {} (slot {}): {}",
+                    timestamp.format("%H:%M:%S"), alert.rule, alert.account, alert.slot, alert.message);
+                Ok(())
+            }
+            Output::File { path } => OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| writeln!(file, "{}", payload))
+                .map_err(Into::into),
+            Output::Webhook { url } => match &rule_set.http {
+                Some(http) => http.post(url)
+                    .json(&payload)
+                    .send()
+                    .and_then(|response| response.error_for_status())
+                    .map(|_| ())
+                    .map_err(Into::into),
+                None => Err(anyhow!("no HTTP client")),
+            },
+        };
+
+        if let Err(e) = result {
+            println!("⚠️  Failed to deliver alert '{}' to {:?}: {}", alert.rule, output, e);
+        }
+    }
+}
+
+/// Evaluates every rule against a change and delivers the alerts that fire
+pub fn process_change(rule_set: &RuleSet, registry: &DecoderRegistry, slot: u64, change: &Change, snapshot: &LiveSnapshot) {
+    let account = change_account(change);
+    let keys: HashSet<Pubkey> = snapshot.accounts.keys().copied().collect();
+    let layout = |account: &SnapshotAccount| {
+        registry.layout(&account.data, &snapshot.owner_of(&account.pubkey), &keys)
+    };
+    for rule in &rule_set.rules {
+        if let Some(message) = rule.evaluate(change, &layout) {
+            let alert = Alert {
+                rule: rule.name.clone(),
+                account: account.pubkey,
+                slot,
+                message,
+            };
+            deliver(rule_set, &snapshot.program_id, &alert);
+        }
+    }
+}
+
+/// Runs alert rules on top of watch mode, or a polling loop with `--poll SECONDS`.
+/// Accepts the same filter and decoder flags as `spade watch`
+pub fn run_alerts(fetcher: &mut AccountFetcher, rules_path: &str, args: &[String]) -> Result<()> {
+    let rule_set = RuleSet::load(rules_path)?;
+    let filters = filters::from_args(args, &["--poll", "--idl", "--decoders"])?;
+    let registry = DecoderRegistry::from_args(args)?;
+    let poll = filters::flag_values(args, "--poll").first().map(|s| s.parse::<u64>()).transpose()?;
+
+    println!("\n🚨 ALERT MODE");
+    println!("============");
+    println!("Loaded {} rules from {}", rule_set.rules.len(), rules_path);
+    for rule in &rule_set.rules {
+        println!("- {} ({:?})", rule.name, rule.kind);
+    }
+
+    let on_change = |slot: u64, change: &Change, snapshot: &LiveSnapshot| {
+        process_change(&rule_set, &registry, slot, change, snapshot);
+        Ok(())
+    };
+
+    // Accounts named by rules are watched too, they may belong to another program
+    let mut accounts = rule_set.rules.iter()
+        .filter_map(|r| r.account.as_deref())
+        .map(Pubkey::from_str)
+        .collect::<Result<Vec<_>, _>>()?;
+    accounts.sort_unstable();
+    accounts.dedup();
+
+    match poll {
+        Some(seconds) => watch::run_polling(fetcher, filters, accounts, Duration::from_secs(seconds.max(1)), on_change),
+        None => watch::run_subscriptions(fetcher, filters, accounts, on_change),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(value: u64) -> SnapshotAccount {
+        SnapshotAccount { pubkey: Pubkey::default(), lamports: 1, data: value.to_le_bytes().to_vec() }
+    }
+
+    fn no_layout(_: &SnapshotAccount) -> Vec<Field> {
+        Vec::new()
+    }
+
+    fn threshold(below: Option<&str>, above: Option<&str>) -> Rule {
+        let mut yaml = "rule:\n  - name: t\n    kind: threshold\n".to_string();
+        if let Some(below) = below {
+            yaml += &format!("    below: {}\n", below);
+        }
+        if let Some(above) = above {
+            yaml += &format!("    above: {}\n", above);
+        }
+        RuleSet::validate(serde_yaml::from_str(&yaml).unwrap()).unwrap().rules.remove(0)
+    }
+
+    #[test]
+    fn parses_toml_and_yaml() {
+        let toml = "[[rule]]\nname = \"a\"\nkind = \"closed\"\n[[output]]\ntype = \"file\"\npath = \"x.log\"\n";
+        let yaml = "rule:\n  - name: a\n    kind: closed\noutput:\n  - type: file\n    path: x.log\n";
+        for rule_set in [toml::from_str::<RuleSet>(toml).unwrap(), serde_yaml::from_str(yaml).unwrap()] {
+            let rule_set = RuleSet::validate(rule_set).unwrap();
+            assert_eq!(rule_set.rules[0].kind, RuleKind::Closed);
+            assert!(matches!(&rule_set.outputs[..], [Output::File { path }] if path == "x.log"));
+            assert!(rule_set.http.is_none());
+        }
+        assert!(RuleSet::validate(toml::from_str("").unwrap()).is_err());
+    }
+
+    #[test]
+    fn u64_bounds_above_i64_max() {
+        let rule = threshold(None, Some("\"18000000000000000000\""));
+        assert_eq!(rule.above, Some(Bound(18_000_000_000_000_000_000)));
+        let change = Change::Updated(account(1), account(u64::MAX));
+        assert!(rule.evaluate(&change, &no_layout).is_some());
+
+        let rule = threshold(None, Some("18000000000000000000"));
+        assert_eq!(rule.above, Some(Bound(18_000_000_000_000_000_000)));
+        assert!(Bound::try_from(BoundValue::Text("-1e3".to_string())).is_err());
+    }
+
+    #[test]
+    fn fires_on_crossings_only() {
+        let rule = threshold(Some("10"), Some("100"));
+        let fires = |before, after| rule.evaluate(&Change::Updated(account(before), account(after)), &no_layout).is_some();
+        assert!(fires(50, 5));
+        assert!(!fires(5, 4));
+        assert!(fires(5, 500));
+        assert!(fires(500, 5));
+        assert!(!fires(500, 50));
+    }
+
+    #[test]
+    fn resolves_named_fields_through_the_layout() {
+        let yaml = "rule:
+  - name: authority
+    kind: field_changed
+    field: authority
+  - name: low
+    kind: threshold
+    field: amount
+    below: 10
+";
+        let rules = RuleSet::validate(serde_yaml::from_str(yaml).unwrap()).unwrap().rules;
+        let (authority, new_authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let vault = |authority: &Pubkey, amount: u32, grown: bool| {
+            // A later layout version inserts a u64 before the fields
+            let mut data = if grown { vec![0; 8] } else { Vec::new() };
+            data.extend_from_slice(authority.as_ref());
+            data.extend_from_slice(&amount.to_le_bytes());
+            SnapshotAccount { pubkey: Pubkey::default(), lamports: 1, data }
+        };
+        let layout = |account: &SnapshotAccount| {
+            let start = account.data.len() - 36;
+            let authority = Pubkey::try_from(&account.data[start..start + 32]).unwrap();
+            vec![
+                Field::new(start, 32, "authority", authority.to_string()),
+                Field::new(start + 32, 4, "amount", "?"),
+            ]
+        };
+
+        let changed = Change::Updated(vault(&authority, 50, false), vault(&new_authority, 50, true));
+        assert_eq!(rules[0].evaluate(&changed, &layout),
+            Some(format!("authority changed: {} → {}", authority, new_authority)));
+        assert_eq!(rules[1].evaluate(&changed, &layout), None);
+
+        let moved = Change::Updated(vault(&authority, 50, false), vault(&authority, 5, true));
+        assert_eq!(rules[0].evaluate(&moved, &layout), None);
+        assert_eq!(rules[1].evaluate(&moved, &layout), Some("amount went 50 → 5 (below 10)".to_string()));
+
+        let both = "rule:\n  - name: x\n    kind: field_changed\n    field: authority\n    len: 32\n";
+        assert!(RuleSet::validate(serde_yaml::from_str(both).unwrap()).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Result};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+
+use crate::filters;
+use crate::AccountFetcher;
+
+/// Buffers closed per transaction
+const CLOSES_PER_TX: usize = 8;
+
+/// Attempts per batch before giving up; each attempt already cycles through the RPC endpoints
+const CLOSE_ATTEMPTS: usize = 3;
+
+/// Offset of the authority pubkey in a Buffer account: u32 state tag + Option tag
+const BUFFER_AUTHORITY_OFFSET: usize = 5;
+
+/// An upgradeable loader buffer account
+pub struct BufferAccount {
+    pub address: Pubkey,
+    pub lamports: u64,
+    /// Program bytes the buffer can hold, excluding the metadata header
+    pub program_len: usize,
+}
+
+/// getProgramAccounts filters matching `Buffer { authority_address: Some(authority) }`
+fn buffer_filters(authority: &Pubkey) -> Vec<RpcFilterType> {
+    vec![
+        RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, vec![1, 0, 0, 0])),
+        RpcFilterType::Memcmp(Memcmp::new_raw_bytes(4, vec![1])),
+        RpcFilterType::Memcmp(Memcmp::new_raw_bytes(BUFFER_AUTHORITY_OFFSET, authority.to_bytes().to_vec())),
+    ]
+}
+
+/// Finds every buffer account whose authority is `authority`
+pub fn find_buffers(fetcher: &mut AccountFetcher, authority: &Pubkey) -> Result<Vec<BufferAccount>> {
+    let mut buffers: Vec<_> = fetcher.get_filtered_accounts(buffer_filters(authority))?
+        .into_iter()
+        .map(|(address, account)| BufferAccount {
+            address,
+            lamports: account.lamports,
+            program_len: account.data.len().saturating_sub(UpgradeableLoaderState::size_of_buffer_metadata()),
+        })
+        .collect();
+    buffers.sort_by_key(|b| std::cmp::Reverse(b.lamports));
+    Ok(buffers)
+}
+
+/// Closes a batch of buffers in one transaction. Buffers already gone are skipped, so a
+/// retry after a send that landed but timed out does not fail on the closed accounts
+fn close_batch(
+    fetcher: &mut AccountFetcher,
+    batch: &[Pubkey],
+    authority: &Keypair,
+    recipient: &Pubkey,
+    dry_run: bool,
+) -> Result<Option<String>> {
+    fetcher.with_retry(|client| {
+        let existing = client.get_multiple_accounts(batch)?;
+        let instructions: Vec<_> = batch.iter()
+            .zip(existing)
+            .filter(|(_, account)| account.is_some())
+            .map(|(buffer, _)| bpf_loader_upgradeable::close(buffer, recipient, &authority.pubkey()))
+            .collect();
+        if instructions.is_empty() {
+            return Ok(None);
+        }
+
+        let blockhash = client.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&authority.pubkey()), &[authority], blockhash);
+
+        if dry_run {
+            let result = client.simulate_transaction(&tx)?.value;
+            return match result.err {
+                Some(err) => Err(anyhow!("simulation failed: {} {:?}", err, result.logs.unwrap_or_default())),
+                None => Ok(Some(format!("simulated OK ({} compute units)", result.units_consumed.unwrap_or(0)))),
+            };
+        }
+
+        Ok(Some(client.send_and_confirm_transaction(&tx)?.to_string()))
+    })
+}
+
+/// Lists the upgradeable loader buffers owned by an authority and the rent they lock,
+/// optionally closing them.
+/// Flags: `--close --keypair FILE`, `--recipient PUBKEY` (defaults to the authority), `--dry-run`
+pub fn reclaim_buffers(authority: &str, args: &[String]) -> Result<()> {
+    filters::check_flags(args, &["--close", "--dry-run", "--keypair", "--recipient"])?;
+    let authority = Pubkey::from_str(authority)?;
+    let close = args.iter().any(|a| a == "--close");
+    let dry_run = args.iter().any(|a| a == "--dry-run");
+    let keypair_path = filters::flag_values(args, "--keypair").into_iter().next();
+    let recipient = filters::flag_values(args, "--recipient")
+        .first()
+        .map(|r| Pubkey::from_str(r))
+        .transpose()?
+        .unwrap_or(authority);
+
+    println!("\n♻️  BUFFER ACCOUNTS");
+    println!("==================");
+    println!("Authority: {}", authority);
+
+    let mut fetcher = AccountFetcher::new(&bpf_loader_upgradeable::id().to_string())?;
+    let buffers = find_buffers(&mut fetcher, &authority)?;
+    if buffers.is_empty() {
+        println!("\nNo buffer accounts found");
+        return Ok(());
+    }
+
+    println!("\n{:<44} {:>12} {:>16}", "Buffer", "Size", "Lamports");
+    for buffer in &buffers {
+        println!("{:<44} {:>12} {:>16}", buffer.address.to_string(), buffer.program_len, buffer.lamports);
+    }
+    let total: u64 = buffers.iter().map(|b| b.lamports).sum();
+    println!("\n{} buffers, {:.6} SOL reclaimable", buffers.len(), total as f64 / LAMPORTS_PER_SOL as f64);
+
+    if !close && !dry_run {
+        println!("Run with --close --keypair FILE to reclaim (add --dry-run to simulate first)");
+        return Ok(());
+    }
+
+    let keypair_path = keypair_path.ok_or_else(|| anyhow!("--keypair FILE is required to close buffers"))?;
+    let keypair = read_keypair_file(&keypair_path)
+        .map_err(|e| anyhow!("Failed to read keypair {}: {}", keypair_path, e))?;
+    if keypair.pubkey() != authority {
+        bail!("Keypair {} is not the buffer authority {}", keypair.pubkey(), authority);
+    }
+
+    println!("\n{} {} buffers, sending rent to {}",
+        if dry_run { "Simulating close of" } else { "Closing" }, buffers.len(), recipient);
+
+    let addresses: Vec<Pubkey> = buffers.iter().map(|b| b.address).collect();
+    let mut failed = 0;
+    for batch in addresses.chunks(CLOSES_PER_TX) {
+        let mut result = close_batch(&mut fetcher, batch, &keypair, &recipient, dry_run);
+        for attempt in 2..=CLOSE_ATTEMPTS {
+            let Err(e) = &result else { break };
+            println!("⚠️  Attempt {} failed: {}, retrying...", attempt - 1, e);
+            thread::sleep(Duration::from_secs(2));
+            result = close_batch(&mut fetcher, batch, &keypair, &recipient, dry_run);
+        }
+
+        match result {
+            Ok(Some(result)) => println!("✅ {} buffers: {}", batch.len(), result),
+            Ok(None) => println!("✅ {} buffers already closed", batch.len()),
+            Err(e) => {
+                failed += batch.len();
+                println!("❌ {} buffers: {}", batch.len(), e);
+            }
+        }
+    }
+
+    if failed > 0 {
+        bail!("{} buffers could not be closed", failed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer(authority: Option<Pubkey>) -> Vec<u8> {
+        let mut data = bincode::serialize(&UpgradeableLoaderState::Buffer { authority_address: authority }).unwrap();
+        data.extend_from_slice(&[0x7f; 16]);
+        data
+    }
+
+    #[test]
+    fn filters_buffers_by_authority() {
+        let authority = Pubkey::new_unique();
+        let filters = buffer_filters(&authority);
+
+        let data = buffer(Some(authority));
+        assert_eq!(&data[BUFFER_AUTHORITY_OFFSET..BUFFER_AUTHORITY_OFFSET + 32], authority.as_ref());
+        assert!(filters::matches(&filters, &data));
+        assert!(!filters::matches(&filters, &buffer(Some(Pubkey::new_unique()))));
+        assert!(!filters::matches(&filters, &buffer(None)));
+
+        let program_data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+            slot: 1,
+            upgrade_authority_address: Some(authority),
+        }).unwrap();
+        assert!(!filters::matches(&filters, &program_data));
+    }
+}
@@ -0,0 +1,410 @@
+use chrono::DateTime;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::idl;
+use crate::layout::{Field, Reader};
+use crate::metaplex;
+
+pub const CANDY_MACHINE_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("CndyV3LdqHUfDLmE5naZjVN8rBZz4tqhdefbAnjHG3JR");
+pub const CANDY_GUARD_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("Guard1JwRhJkVH6XZhzoYxeBVQe872VH6QggF4BWmS9g");
+
+/// Start of the config line section: the candy machine struct with every variable-length
+/// field at its maximum size (symbol 10, 5 creators, names 32, URIs 200)
+const HIDDEN_SECTION: usize = 850;
+
+/// Loaded config lines shown in the specific account view
+const CONFIG_LINES_SHOWN: usize = 10;
+
+/// Candy Guard data (the default guard set and groups) follows discriminator, base, bump
+/// and authority
+const GUARD_DATA_OFFSET: usize = 8 + 32 + 1 + 32;
+
+/// Length of a guard group label
+const GROUP_LABEL_LEN: usize = 6;
+
+/// Guards in `features` bit order
+const GUARD_NAMES: &[&str] = &[
+    "bot_tax",
+    "sol_payment",
+    "token_payment",
+    "start_date",
+    "third_party_signer",
+    "token_gate",
+    "gatekeeper",
+    "end_date",
+    "allow_list",
+    "mint_limit",
+    "nft_payment",
+    "redeemed_amount",
+    "address_gate",
+    "nft_gate",
+    "nft_burn",
+    "token_burn",
+    "freeze_sol_payment",
+    "freeze_token_payment",
+    "program_gate",
+    "allocation",
+    "token2022_payment",
+    "sol_fixed_fee",
+    "nft_mint_limit",
+    "edition",
+];
+
+/// Addresses a program gate can list
+const PROGRAM_GATE_MAX: usize = 5;
+
+/// A decoded Candy Machine or Candy Guard account
+#[derive(Debug, Clone)]
+pub struct CandyAccount {
+    pub kind: &'static str,
+    pub fields: Vec<Field>,
+    /// Summary lines printed after the fields, such as loaded config lines
+    pub notes: Vec<String>,
+}
+
+pub fn is_candy_program(program_id: &Pubkey) -> bool {
+    *program_id == CANDY_MACHINE_PROGRAM_ID || *program_id == CANDY_GUARD_PROGRAM_ID
+}
+
+fn format_lamports(lamports: u64) -> String {
+    format!("{} ({} SOL)", lamports, lamports as f64 / LAMPORTS_PER_SOL as f64)
+}
+
+fn lamports(reader: &mut Reader, name: &str) -> Option<u64> {
+    let value = u64::from_le_bytes(reader.peek(8)?.try_into().ok()?);
+    reader.take(8, name, format_lamports(value)).map(|_| value)
+}
+
+fn date(reader: &mut Reader, name: &str) -> Option<i64> {
+    let value = i64::from_le_bytes(reader.peek(8)?.try_into().ok()?);
+    let formatted = DateTime::from_timestamp(value, 0)
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| value.to_string());
+    reader.take(8, name, formatted).map(|_| value)
+}
+
+fn hex_bytes(reader: &mut Reader, len: usize, name: &str) -> Option<()> {
+    let value = hex::encode(reader.peek(len)?);
+    reader.take(len, name, value).map(|_| ())
+}
+
+/// Reads a trimmed fixed-size string, as used for config lines and group labels
+fn fixed_str(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).trim_end_matches('\0').to_string()
+}
+
+/// Decodes one enabled guard. Guards occupy their maximum size, so a program gate is
+/// always followed by room for `PROGRAM_GATE_MAX` addresses
+fn read_guard(reader: &mut Reader, guard: &str, prefix: &str) -> Option<()> {
+    let name = |field: &str| format!("{}.{}.{}", prefix, guard, field);
+    match guard {
+        "bot_tax" => {
+            lamports(reader, &name("lamports"))?;
+            reader.bool(&name("last_instruction"))?;
+        }
+        "sol_payment" | "freeze_sol_payment" | "sol_fixed_fee" => {
+            lamports(reader, &name("lamports"))?;
+            reader.pubkey(&name("destination"))?;
+        }
+        "token_payment" | "freeze_token_payment" | "token2022_payment" => {
+            reader.u64(&name("amount"))?;
+            reader.pubkey(&name("mint"))?;
+            reader.pubkey(&name("destination_ata"))?;
+        }
+        "start_date" | "end_date" => {
+            date(reader, &name("date"))?;
+        }
+        "third_party_signer" => {
+            reader.pubkey(&name("signer_key"))?;
+        }
+        "token_gate" | "token_burn" => {
+            reader.u64(&name("amount"))?;
+            reader.pubkey(&name("mint"))?;
+        }
+        "gatekeeper" => {
+            reader.pubkey(&name("gatekeeper_network"))?;
+            reader.bool(&name("expire_on_use"))?;
+        }
+        "allow_list" => hex_bytes(reader, 32, &name("merkle_root"))?,
+        "mint_limit" => {
+            reader.u8(&name("id"))?;
+            reader.u16(&name("limit"))?;
+        }
+        "nft_payment" => {
+            reader.pubkey(&name("required_collection"))?;
+            reader.pubkey(&name("destination"))?;
+        }
+        "redeemed_amount" => {
+            reader.u64(&name("maximum"))?;
+        }
+        "address_gate" => {
+            reader.pubkey(&name("address"))?;
+        }
+        "nft_gate" | "nft_burn" => {
+            reader.pubkey(&name("required_collection"))?;
+        }
+        "program_gate" => {
+            let count = (reader.u32(&name("additional.len"))? as usize).min(PROGRAM_GATE_MAX);
+            let start = reader.offset;
+            for i in 0..count {
+                reader.pubkey(&name(&format!("additional[{}]", i)))?;
+            }
+            reader.offset = start + PROGRAM_GATE_MAX * 32;
+        }
+        "allocation" => {
+            reader.u8(&name("id"))?;
+            reader.u32(&name("limit"))?;
+        }
+        "nft_mint_limit" => {
+            reader.u8(&name("id"))?;
+            reader.u16(&name("limit"))?;
+            reader.pubkey(&name("required_collection"))?;
+        }
+        "edition" => {
+            reader.u32(&name("edition_start_offset"))?;
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+/// Decodes a guard set: a u64 bitmask of enabled guards followed by each guard's data.
+/// Returns the names of the enabled guards
+fn read_guard_set(reader: &mut Reader, prefix: &str) -> Option<Vec<&'static str>> {
+    let features = u64::from_le_bytes(reader.peek(8)?.try_into().ok()?);
+    reader.take(8, &format!("{}.features", prefix), format!("{:#x}", features))?;
+
+    let mut enabled = Vec::new();
+    for bit in 0..64 {
+        if features & (1 << bit) == 0 {
+            continue;
+        }
+        // Guards added after this table have unknown sizes, so decoding stops there
+        let guard = GUARD_NAMES.get(bit)?;
+        read_guard(reader, guard, prefix)?;
+        enabled.push(*guard);
+    }
+    Some(enabled)
+}
+
+fn decode_candy_guard(data: &[u8]) -> CandyAccount {
+    let mut reader = Reader::new(data);
+    let mut notes = Vec::new();
+    reader.take(8, "discriminator", "CandyGuard".to_string());
+    reader.pubkey("base");
+    reader.u8("bump");
+    reader.pubkey("authority");
+
+    reader.offset = GUARD_DATA_OFFSET;
+    match read_guard_set(&mut reader, "default") {
+        Some(guards) => notes.push(format!("Default guards: {}", guards.join(", "))),
+        None => {
+            notes.push("⚠️  Default guard set uses guards this version cannot decode".to_string());
+            return CandyAccount { kind: "Candy Guard", fields: reader.fields, notes };
+        }
+    }
+
+    if let Some(groups) = reader.u32("groups.len") {
+        for _ in 0..groups {
+            let Some(label) = reader.peek(GROUP_LABEL_LEN).map(fixed_str) else { break };
+            reader.take(GROUP_LABEL_LEN, "group.label", format!("{:?}", label));
+            match read_guard_set(&mut reader, &format!("group[{}]", label)) {
+                Some(guards) => notes.push(format!("Group {:?}: {}", label, guards.join(", "))),
+                None => {
+                    notes.push(format!("⚠️  Group {:?} uses guards this version cannot decode", label));
+                    break;
+                }
+            }
+        }
+    }
+
+    CandyAccount { kind: "Candy Guard", fields: reader.fields, notes }
+}
+
+/// Name and URI length of each config line, and their shared prefixes
+struct ConfigLineSettings {
+    prefix_name: String,
+    name_length: usize,
+    prefix_uri: String,
+    uri_length: usize,
+}
+
+/// What the config line section of a candy machine depends on
+#[derive(Default)]
+struct CandyMachineHeader {
+    items_redeemed: u64,
+    items_available: u64,
+    config_line_settings: Option<ConfigLineSettings>,
+    hidden_settings: bool,
+}
+
+/// Reads the candy machine struct, stopping at the first field past the end of the data
+fn read_candy_machine(reader: &mut Reader, header: &mut CandyMachineHeader) -> Option<()> {
+    reader.take(8, "discriminator", "CandyMachine".to_string())?;
+    reader.named_u8("version", &["V1", "V2"])?;
+    reader.named_u8("token_standard", metaplex::TOKEN_STANDARDS)?;
+    hex_bytes(reader, 6, "features")?;
+    reader.pubkey("authority")?;
+    reader.pubkey("mint_authority")?;
+    reader.pubkey("collection_mint")?;
+    header.items_redeemed = reader.u64("items_redeemed")?;
+    header.items_available = reader.u64("items_available")?;
+    reader.string("symbol")?;
+    reader.u16("seller_fee_basis_points")?;
+    reader.u64("max_supply")?;
+    reader.bool("is_mutable")?;
+    let creators = reader.u32("creators.len")?;
+    for i in 0..creators {
+        reader.pubkey(&format!("creators[{}].address", i))?;
+        reader.bool(&format!("creators[{}].verified", i))?;
+        reader.u8(&format!("creators[{}].percentage_share", i))?;
+    }
+    if reader.option("config_line_settings")? {
+        header.config_line_settings = Some(ConfigLineSettings {
+            prefix_name: reader.string("config_line_settings.prefix_name")?,
+            name_length: reader.u32("config_line_settings.name_length")? as usize,
+            prefix_uri: reader.string("config_line_settings.prefix_uri")?,
+            uri_length: reader.u32("config_line_settings.uri_length")? as usize,
+        });
+        reader.bool("config_line_settings.is_sequential")?;
+    }
+    if reader.option("hidden_settings")? {
+        header.hidden_settings = true;
+        reader.string("hidden_settings.name")?;
+        reader.string("hidden_settings.uri")?;
+        hex_bytes(reader, 32, "hidden_settings.hash")?;
+    }
+    Some(())
+}
+
+fn decode_candy_machine(data: &[u8]) -> CandyAccount {
+    let mut reader = Reader::new(data);
+    let mut notes = Vec::new();
+    let mut header = CandyMachineHeader::default();
+    read_candy_machine(&mut reader, &mut header);
+
+    let CandyMachineHeader { items_redeemed, items_available, config_line_settings, hidden_settings } = header;
+    notes.push(format!("Items: {} available, {} redeemed, {} remaining",
+        items_available, items_redeemed, items_available.saturating_sub(items_redeemed)));
+
+    match config_line_settings {
+        _ if hidden_settings => notes.push("Hidden settings: every mint gets the same name and URI".to_string()),
+        Some(settings) => {
+            reader.offset = HIDDEN_SECTION;
+            let loaded = reader.u32("items_loaded").unwrap_or(0);
+            notes.push(format!("Config lines loaded: {} of {}", loaded, items_available));
+
+            let line_len = settings.name_length + settings.uri_length;
+            let lines_start = HIDDEN_SECTION + 4;
+            let shown: Vec<String> = (0..items_available as usize)
+                .filter_map(|i| data.get(lines_start + i * line_len..lines_start + (i + 1) * line_len).map(|line| (i, line)))
+                .filter(|(_, line)| line.iter().any(|&b| b != 0))
+                .take(CONFIG_LINES_SHOWN)
+                .map(|(i, line)| {
+                    let (name, uri) = line.split_at(settings.name_length);
+                    format!("  #{:<5} {}{}  {}{}", i, settings.prefix_name, fixed_str(name), settings.prefix_uri, fixed_str(uri))
+                })
+                .collect();
+            if !shown.is_empty() {
+                notes.push(format!("First {} config lines:", shown.len()));
+                notes.extend(shown);
+            }
+        }
+        None => {}
+    }
+
+    CandyAccount { kind: "Candy Machine", fields: reader.fields, notes }
+}
+
+/// Decodes Candy Machine v3 and Candy Guard accounts by their Anchor discriminator
+pub fn decode(data: &[u8]) -> Option<CandyAccount> {
+    let discriminator = data.get(..8)?;
+    if discriminator == idl::anchor_discriminator("account", "CandyMachine") {
+        Some(decode_candy_machine(data))
+    } else if discriminator == idl::anchor_discriminator("account", "CandyGuard") {
+        Some(decode_candy_guard(data))
+    } else {
+        None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn borsh_string(data: &mut Vec<u8>, text: &str) {
+        data.extend_from_slice(&(text.len() as u32).to_le_bytes());
+        data.extend_from_slice(text.as_bytes());
+    }
+
+    fn candy_guard(default_features: u64) -> Vec<u8> {
+        let mut data = idl::anchor_discriminator("account", "CandyGuard").to_vec();
+        data.extend_from_slice(&[0; 65]);
+        data.extend_from_slice(&default_features.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn decodes_guard_sets_and_groups() {
+        // sol_payment and start_date
+        let mut data = candy_guard(0b1010);
+        data.extend_from_slice(&LAMPORTS_PER_SOL.to_le_bytes());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(&0i64.to_le_bytes());
+        // One "public" group with mint_limit
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(b"public");
+        data.extend_from_slice(&(1u64 << 9).to_le_bytes());
+        data.extend_from_slice(&[1, 3, 0]);
+
+        let account = decode(&data).unwrap();
+        assert_eq!(account.kind, "Candy Guard");
+        assert_eq!(account.notes, ["Default guards: sol_payment, start_date", "Group \"public\": mint_limit"]);
+        let field = |name: &str| account.fields.iter().find(|f| f.name == name).map(|f| f.value.as_str());
+        assert_eq!(field("default.sol_payment.lamports"), Some("1000000000 (1 SOL)"));
+        assert_eq!(field("default.start_date.date"), Some("1970-01-01 00:00:00 UTC"));
+        assert_eq!(field("group[public].mint_limit.limit"), Some("3"));
+    }
+
+    #[test]
+    fn stops_at_unknown_guards() {
+        let account = decode(&candy_guard(1 << 40)).unwrap();
+        assert_eq!(account.notes, ["⚠️  Default guard set uses guards this version cannot decode"]);
+    }
+
+    #[test]
+    fn decodes_config_lines() {
+        let mut data = idl::anchor_discriminator("account", "CandyMachine").to_vec();
+        data.extend_from_slice(&[1, 0]);
+        data.extend_from_slice(&[0; 6 + 96]);
+        data.extend_from_slice(&1u64.to_le_bytes());
+        data.extend_from_slice(&3u64.to_le_bytes());
+        borsh_string(&mut data, "SPD");
+        data.extend_from_slice(&[0; 2 + 8 + 1 + 4]);
+        data.push(1);
+        borsh_string(&mut data, "Spade #");
+        data.extend_from_slice(&4u32.to_le_bytes());
+        borsh_string(&mut data, "https://");
+        data.extend_from_slice(&8u32.to_le_bytes());
+        data.extend_from_slice(&[0, 0]);
+
+        data.resize(HIDDEN_SECTION, 0);
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(b"1\0\0\0a.json\0\0");
+        data.extend_from_slice(&[0; 12]);
+        data.extend_from_slice(b"3\0\0\0c.json\0\0");
+
+        let account = decode(&data).unwrap();
+        assert_eq!(account.kind, "Candy Machine");
+        assert_eq!(account.notes, [
+            "Items: 3 available, 1 redeemed, 2 remaining",
+            "Config lines loaded: 2 of 3",
+            "First 2 config lines:",
+            "  #0     Spade #1  https://a.json",
+            "  #2     Spade #3  https://c.json",
+        ]);
+        assert!(decode(&[0; 8]).is_none());
+    }
+}
@@ -0,0 +1,281 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Result;
+use solana_sdk::compute_budget;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::idl;
+use crate::logs::LogPatterns;
+use crate::txs::{self, Ix, ProgramTx};
+use crate::AccountFetcher;
+
+/// Default compute unit limit per instruction when a transaction does not set one
+const DEFAULT_IX_COMPUTE_LIMIT: u64 = 200_000;
+
+/// Maximum compute unit limit of a transaction
+const MAX_TX_COMPUTE_LIMIT: u64 = 1_400_000;
+
+/// Base fee per signature, in lamports
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Headroom added on top of the observed p99 when suggesting a compute unit limit
+const SUGGESTED_LIMIT_HEADROOM: f64 = 1.1;
+
+/// Compute budget settings of a transaction, from its ComputeBudget instructions
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ComputeBudget {
+    /// SetComputeUnitLimit, if present
+    pub unit_limit: Option<u32>,
+    /// SetComputeUnitPrice in micro-lamports per compute unit, if present
+    pub unit_price: Option<u64>,
+}
+
+impl ComputeBudget {
+    /// Reads the ComputeBudget instructions of a transaction
+    pub fn from_instructions(instructions: &[Ix]) -> Self {
+        let mut budget = Self::default();
+        for ix in instructions.iter().filter(|ix| ix.program_id == compute_budget::id()) {
+            match ix.data.split_first() {
+                Some((2, rest)) => budget.unit_limit = rest.get(..4)
+                    .map(|b| u32::from_le_bytes(b.try_into().unwrap())),
+                Some((3, rest)) => budget.unit_price = rest.get(..8)
+                    .map(|b| u64::from_le_bytes(b.try_into().unwrap())),
+                _ => {}
+            }
+        }
+        budget
+    }
+
+    /// The limit the runtime applied: the explicit limit, or the default per instruction
+    pub fn effective_limit(&self, instructions: &[Ix]) -> u64 {
+        match self.unit_limit {
+            Some(limit) => limit as u64,
+            None => {
+                let ixs = instructions.iter().filter(|ix| ix.program_id != compute_budget::id()).count();
+                (ixs as u64 * DEFAULT_IX_COMPUTE_LIMIT).min(MAX_TX_COMPUTE_LIMIT)
+            }
+        }
+    }
+
+    /// Priority fee in lamports: price × limit, rounded up
+    pub fn priority_fee(&self, instructions: &[Ix]) -> u64 {
+        let price = self.unit_price.unwrap_or(0) as u128;
+        let micro_lamports = price * self.effective_limit(instructions) as u128;
+        micro_lamports.div_ceil(1_000_000) as u64
+    }
+}
+
+/// The program's invocations in execution order: each top-level instruction,
+/// then the instructions it invoked
+fn executed_invocations<'a>(tx: &'a ProgramTx, program_id: &Pubkey) -> Vec<&'a Ix> {
+    let mut ordered = Vec::new();
+    for (index, ix) in tx.instructions.iter().enumerate() {
+        ordered.push(ix);
+        ordered.extend(tx.inner_instructions.iter()
+            .filter(|(i, _)| *i == index)
+            .flat_map(|(_, ixs)| ixs));
+    }
+    ordered.retain(|ix| ix.program_id == *program_id);
+    ordered
+}
+
+/// Value at percentile `p` (0-100) of sorted values
+fn percentile(sorted: &[u64], p: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    sorted[((sorted.len() - 1) * p).div_ceil(100)]
+}
+
+/// Sorts values and formats p50/p90/p99/max
+fn percentiles(values: &mut [u64]) -> String {
+    values.sort_unstable();
+    format!("p50 {:>9}  p90 {:>9}  p99 {:>9}  max {:>9}",
+        percentile(values, 50), percentile(values, 90), percentile(values, 99),
+        values.last().copied().unwrap_or(0))
+}
+
+/// Compute samples for one instruction type
+#[derive(Default)]
+struct InstructionCompute {
+    /// Units consumed by the instruction itself
+    units: Vec<u64>,
+    /// Units consumed by the whole transactions containing it
+    tx_units: Vec<u64>,
+    /// Effective limits of those transactions
+    tx_limits: Vec<u64>,
+    /// Priority fees of those transactions, in lamports
+    priority_fees: Vec<u64>,
+}
+
+/// Per-day compute samples
+#[derive(Default)]
+struct DayCompute {
+    txs: usize,
+    units: Vec<u64>,
+    priority_fees: Vec<u64>,
+}
+
+/// Profiles compute unit usage and priority fees per instruction type from recent transactions
+pub fn analyze_compute(fetcher: &mut AccountFetcher, args: &[String]) -> Result<()> {
+    let limit = txs::limit_from_args(args)?;
+    let idl = idl::from_args(args)?;
+    let program_id = fetcher.program_id;
+    let program_str = program_id.to_string();
+
+    println!("\n⚡ COMPUTE UNIT & PRIORITY FEE PROFILE");
+    println!("=====================================");
+
+    let txs = txs::fetch_transactions(fetcher, &program_id, limit)?;
+    if txs.is_empty() {
+        println!("\nNo transactions found");
+        return Ok(());
+    }
+
+    let mut tx_units = Vec::new();
+    let mut utilization = Vec::new();
+    let mut prices = Vec::new();
+    let mut priority_fees = Vec::new();
+    let mut explicit_limits = 0;
+    let mut per_instruction: HashMap<String, InstructionCompute> = HashMap::new();
+    let mut per_day: BTreeMap<String, DayCompute> = BTreeMap::new();
+
+    for tx in &txs {
+        let budget = ComputeBudget::from_instructions(&tx.instructions);
+        let tx_limit = budget.effective_limit(&tx.instructions);
+        let priority_fee = budget.priority_fee(&tx.instructions);
+        if budget.unit_limit.is_some() {
+            explicit_limits += 1;
+        }
+        if let Some(price) = budget.unit_price {
+            prices.push(price);
+        }
+        priority_fees.push(priority_fee);
+
+        if let Some(units) = tx.compute_units {
+            tx_units.push(units);
+            utilization.extend((units * 100).checked_div(tx_limit));
+        }
+
+        let day = tx.block_time.map(txs::format_day).unwrap_or_else(|| "unknown".to_string());
+        let day = per_day.entry(day).or_default();
+        day.txs += 1;
+        day.units.extend(tx.compute_units);
+        day.priority_fees.push(priority_fee);
+
+        let units = LogPatterns::get().compute_units(&tx.logs, &program_str);
+        for (i, ix) in executed_invocations(tx, &program_id).into_iter().enumerate() {
+            let stats = per_instruction.entry(txs::instruction_label(idl.as_ref(), &ix.data)).or_default();
+            stats.units.extend(units.get(i).copied().flatten());
+            stats.tx_units.extend(tx.compute_units);
+            stats.tx_limits.push(tx_limit);
+            stats.priority_fees.push(priority_fee);
+        }
+    }
+
+    println!("\nAnalyzed {} transactions", txs.len());
+    println!("Explicit compute unit limit: {} txs ({:.1}%)",
+        explicit_limits, 100.0 * explicit_limits as f64 / txs.len() as f64);
+    println!("Compute unit price set:      {} txs ({:.1}%)",
+        prices.len(), 100.0 * prices.len() as f64 / txs.len() as f64);
+    let base_fees: u64 = txs.iter().map(|tx| tx.num_signers as u64 * LAMPORTS_PER_SIGNATURE).sum();
+    let total_priority: u64 = priority_fees.iter().sum();
+    println!("Fees paid: {} lamports ({} base, {} priority)",
+        txs.iter().map(|tx| tx.fee).sum::<u64>(), base_fees, total_priority);
+
+    println!("\n📊 Transaction totals:");
+    println!("  CU consumed:          {}", percentiles(&mut tx_units));
+    println!("  Limit used (%):       {}", percentiles(&mut utilization));
+    println!("  Price (µlamports/CU): {}", percentiles(&mut prices));
+    println!("  Priority fee (lamp.): {}", percentiles(&mut priority_fees));
+
+    let mut ranked: Vec<_> = per_instruction.into_iter().collect();
+    ranked.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.tx_limits.len()));
+
+    println!("\n🧩 Per instruction type:");
+    for (label, mut stats) in ranked {
+        println!("\n  {} ({} invocations)", label, stats.tx_limits.len());
+        if !stats.units.is_empty() {
+            println!("    Instruction CU:  {}", percentiles(&mut stats.units));
+        }
+        if !stats.tx_units.is_empty() {
+            println!("    Transaction CU:  {}", percentiles(&mut stats.tx_units));
+        }
+        println!("    CU limit:        {}", percentiles(&mut stats.tx_limits));
+        println!("    Priority fee:    {}", percentiles(&mut stats.priority_fees));
+
+        let (p99, median_limit) = (percentile(&stats.tx_units, 99), percentile(&stats.tx_limits, 50));
+        if p99 > 0 {
+            let suggested = (p99 as f64 * SUGGESTED_LIMIT_HEADROOM).ceil() as u64;
+            println!("    Suggested limit: {} (p99 + {:.0}%, median requested {})",
+                suggested, (SUGGESTED_LIMIT_HEADROOM - 1.0) * 100.0, median_limit);
+        }
+    }
+
+    println!("\n📅 Trend over time:");
+    println!("  {:<10} {:>6} {:>12} {:>12} {:>16}", "day", "txs", "median CU", "p90 CU", "median prio fee");
+    for (day, mut stats) in per_day {
+        stats.units.sort_unstable();
+        stats.priority_fees.sort_unstable();
+        println!("  {:<10} {:>6} {:>12} {:>12} {:>16}",
+            day, stats.txs,
+            percentile(&stats.units, 50), percentile(&stats.units, 90),
+            percentile(&stats.priority_fees, 50));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::txs::tests::ix;
+
+    fn budget_ix(data: &[u8]) -> Ix {
+        ix(compute_budget::id(), data, &[], 1)
+    }
+
+    fn program_ixs(count: usize) -> Vec<Ix> {
+        (0..count).map(|_| ix(Pubkey::new_unique(), &[], &[], 1)).collect()
+    }
+
+    #[test]
+    fn uses_explicit_unit_limit() {
+        let mut ixs = program_ixs(3);
+        ixs.push(budget_ix(&[&[2][..], &300_000u32.to_le_bytes()].concat()));
+        let budget = ComputeBudget::from_instructions(&ixs);
+        assert_eq!(budget.unit_limit, Some(300_000));
+        assert_eq!(budget.effective_limit(&ixs), 300_000);
+    }
+
+    #[test]
+    fn defaults_per_instruction_up_to_the_cap() {
+        let mut ixs = program_ixs(2);
+        ixs.push(budget_ix(&[&[3][..], &1u64.to_le_bytes()].concat()));
+        assert_eq!(ComputeBudget::from_instructions(&ixs).effective_limit(&ixs), 400_000);
+
+        let ixs = program_ixs(10);
+        assert_eq!(ComputeBudget::default().effective_limit(&ixs), MAX_TX_COMPUTE_LIMIT);
+    }
+
+    #[test]
+    fn priority_fee_rounds_up_to_lamports() {
+        let mut ixs = program_ixs(1);
+        ixs.push(budget_ix(&[&[2][..], &150_001u32.to_le_bytes()].concat()));
+        ixs.push(budget_ix(&[&[3][..], &10u64.to_le_bytes()].concat()));
+        let budget = ComputeBudget::from_instructions(&ixs);
+        assert_eq!(budget.unit_price, Some(10));
+        // 10 micro-lamports × 150,001 units = 1,500,010 micro-lamports
+        assert_eq!(budget.priority_fee(&ixs), 2);
+        assert_eq!(ComputeBudget::default().priority_fee(&ixs), 0);
+    }
+
+    #[test]
+    fn percentiles_of_short_inputs() {
+        assert_eq!(percentile(&[], 99), 0);
+        assert_eq!(percentile(&[7], 50), 7);
+        assert_eq!(percentile(&[7], 99), 7);
+        assert_eq!(percentile(&[1, 2, 3, 4, 5], 50), 3);
+        assert_eq!(percentile(&[1, 2, 3, 4, 5], 90), 5);
+    }
+}
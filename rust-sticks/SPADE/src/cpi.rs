@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::filters;
+use crate::graph::{self, Graph};
+use crate::idl;
+use crate::labels;
+use crate::txs::{self, Ix, ProgramTx};
+use crate::AccountFetcher;
+
+/// Invocation counts for one neighbouring program, per instruction type of this program
+#[derive(Default)]
+struct Neighbour {
+    total: usize,
+    by_instruction: HashMap<String, usize>,
+}
+
+impl Neighbour {
+    fn add(&mut self, instruction: &str) {
+        self.total += 1;
+        *self.by_instruction.entry(instruction.to_string()).or_default() += 1;
+    }
+}
+
+/// A top-level instruction followed by everything it invoked, in execution order
+fn call_sequence(tx: &ProgramTx, index: usize) -> Vec<&Ix> {
+    let mut sequence = vec![&tx.instructions[index]];
+    sequence.extend(tx.inner_instructions.iter()
+        .filter(|(i, _)| *i == index)
+        .flat_map(|(_, ixs)| ixs));
+    sequence
+}
+
+/// One invocation of the program, with the program that invoked it (none at top level) and
+/// the programs it invoked directly
+struct Invocation<'a> {
+    ix: &'a Ix,
+    caller: Option<Pubkey>,
+    callees: Vec<Pubkey>,
+}
+
+/// Attributes every invocation of `program_id` in a transaction from inner instruction
+/// stack heights
+fn attribute_calls<'a>(tx: &'a ProgramTx, program_id: &Pubkey) -> Vec<Invocation<'a>> {
+    let mut invocations = Vec::new();
+    for index in 0..tx.instructions.len() {
+        let sequence = call_sequence(tx, index);
+        for (pos, ix) in sequence.iter().enumerate() {
+            if ix.program_id != *program_id {
+                continue;
+            }
+            let height = ix.stack_height;
+            // The caller is the closest preceding frame one level up
+            let caller = sequence[..pos].iter().rev()
+                .find(|c| height > 1 && c.stack_height == height - 1)
+                .map(|c| c.program_id);
+            // Direct children are the following frames one level down, until control returns
+            let callees = sequence[pos + 1..].iter()
+                .take_while(|c| c.stack_height > height)
+                .filter(|c| c.stack_height == height + 1)
+                .map(|c| c.program_id)
+                .collect();
+            invocations.push(Invocation { ix, caller, callees });
+        }
+    }
+    invocations
+}
+
+/// Prints neighbours ranked by invocation count, with their per-instruction breakdown
+fn print_neighbours(neighbours: &HashMap<Pubkey, Neighbour>) {
+    let mut ranked: Vec<_> = neighbours.iter().collect();
+    ranked.sort_by_key(|(_, n)| std::cmp::Reverse(n.total));
+
+    for (program, neighbour) in ranked {
+        println!("  {} — {} invocations", labels::describe(program), neighbour.total);
+        let mut by_instruction: Vec<_> = neighbour.by_instruction.iter().collect();
+        by_instruction.sort_by_key(|(_, count)| std::cmp::Reverse(**count));
+        for (instruction, count) in by_instruction {
+            println!("      {:<40} {:>6}", instruction, count);
+        }
+    }
+}
+
+/// Builds the graph of programs this program invokes via CPI and programs that invoke it,
+/// from the inner instructions of recent transactions.
+/// Flags: `--limit N`, `--idl FILE`, `--export FILE.dot|FILE.json`
+pub fn analyze_cpi_graph(fetcher: &mut AccountFetcher, args: &[String]) -> Result<()> {
+    let limit = txs::limit_from_args(args)?;
+    let idl = idl::from_args(args)?;
+    let export = filters::flag_values(args, "--export").into_iter().next();
+    let program_id = fetcher.program_id;
+
+    println!("\n🕸️  CPI GRAPH");
+    println!("============");
+
+    let txs = txs::fetch_transactions(fetcher, &program_id, limit)?;
+    if txs.is_empty() {
+        println!("\nNo transactions found");
+        return Ok(());
+    }
+
+    let mut graph = Graph::new(&format!("CPI graph of {}", program_id));
+    let name = idl.as_ref().map(|idl| idl.name.clone()).unwrap_or_else(|| graph::node_label(&program_id));
+    graph.add_node(&program_id, name);
+
+    let mut callers: HashMap<Pubkey, Neighbour> = HashMap::new();
+    let mut callees: HashMap<Pubkey, Neighbour> = HashMap::new();
+    let (mut top_level, mut via_cpi) = (0, 0);
+
+    for tx in &txs {
+        for invocation in attribute_calls(tx, &program_id) {
+            let label = txs::instruction_label(idl.as_ref(), &invocation.ix.data);
+            if invocation.ix.stack_height > 1 {
+                via_cpi += 1;
+            } else {
+                top_level += 1;
+            }
+            if let Some(caller) = invocation.caller {
+                callers.entry(caller).or_default().add(&label);
+                graph.add_edge(&caller, &program_id, &label);
+            }
+            for callee in invocation.callees {
+                callees.entry(callee).or_default().add(&label);
+                graph.add_edge(&program_id, &callee, &label);
+            }
+        }
+    }
+
+    println!("\nAnalyzed {} transactions: {} top-level invocations, {} via CPI", txs.len(), top_level, via_cpi);
+
+    println!("\n⬅️  Programs invoking this program ({}):", callers.len());
+    if callers.is_empty() {
+        println!("  (only invoked directly)");
+    }
+    print_neighbours(&callers);
+
+    println!("\n➡️  Programs this program invokes via CPI ({}):", callees.len());
+    if callees.is_empty() {
+        println!("  (no CPIs)");
+    }
+    print_neighbours(&callees);
+
+    if let Some(path) = export {
+        graph.write(&path)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::txs::tests::{ix, program_tx};
+
+    #[test]
+    fn attributes_nested_cpis() {
+        let [router, amm, program, token, system, other] = [(); 6].map(|_| Pubkey::new_unique());
+        let tx = program_tx(Vec::new(), vec![ix(router, &[], &[], 1), ix(program, &[1], &[], 1)], vec![
+            (0, vec![
+                ix(amm, &[], &[], 2),
+                ix(program, &[2], &[], 3),
+                ix(token, &[], &[], 4),
+                ix(system, &[], &[], 5),
+                ix(token, &[], &[], 4),
+                ix(other, &[], &[], 3),
+            ]),
+            (1, vec![ix(system, &[], &[], 2)]),
+        ]);
+
+        let invocations = attribute_calls(&tx, &program);
+        assert_eq!(invocations.len(), 2);
+        let nested = &invocations[0];
+        assert_eq!((nested.ix.data[0], nested.caller), (2, Some(amm)));
+        assert_eq!(nested.callees, [token, token]);
+        let direct = &invocations[1];
+        assert_eq!((direct.ix.data[0], direct.caller), (1, None));
+        assert_eq!(direct.callees, [system]);
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::candy;
+use crate::filters;
+use crate::idl::{self, Idl};
+use crate::layout::{self, Field};
+use crate::metaplex;
+use crate::native;
+use crate::token;
+
+/// An account decoded into named fields
+#[derive(Debug, Clone)]
+pub struct DecodedAccount {
+    /// Name of the decoder that produced it
+    pub decoder: String,
+    /// Type and state, e.g. `Mint [TransferFeeConfig]` or `Stake (delegated)`
+    pub type_name: String,
+    pub fields: Vec<Field>,
+    /// Summary lines printed after the fields
+    pub notes: Vec<String>,
+}
+
+impl DecodedAccount {
+    pub fn print(&self) {
+        println!("\n🧩 {} ({})", self.type_name, self.decoder);
+        for field in &self.fields {
+            println!("  {:<48} {}", field.name, field.value);
+        }
+        for note in &self.notes {
+            println!("{}", note);
+        }
+    }
+}
+
+/// Decodes the accounts of one program (or family of programs) into named fields.
+///
+/// Anchor programs need no code: pass their IDL with `--idl FILE`, or drop the IDLs in a
+/// directory given with `--decoders DIR` (or SPADE_DECODERS) and every analysis decodes their
+/// accounts. Other layouts implement this trait in a crate depending on SPADE and register it
+/// through `spade::run_with_decoders`
+pub trait AccountDecoder {
+    fn name(&self) -> &str;
+
+    /// Whether this decoder handles `data` owned by `owner`. Cheap: checks the owner or a
+    /// discriminator, not the whole layout
+    fn matches(&self, data: &[u8], owner: &Pubkey) -> bool;
+
+    /// Decodes a matching account, or `None` if its data does not fit the layout
+    fn decode(&self, data: &[u8], owner: &Pubkey) -> Option<DecodedAccount>;
+}
+
+/// Decodes Anchor accounts whose discriminator is in the loaded IDL
+pub struct IdlDecoder {
+    idl: Idl,
+}
+
+impl AccountDecoder for IdlDecoder {
+    fn name(&self) -> &str {
+        "Anchor IDL"
+    }
+
+    fn matches(&self, data: &[u8], _owner: &Pubkey) -> bool {
+        self.idl.account_name(data).is_some()
+    }
+
+    fn decode(&self, data: &[u8], _owner: &Pubkey) -> Option<DecodedAccount> {
+        let (name, fields) = self.idl.decode_account(data)?;
+        let mut notes = Vec::new();
+        let decoded_to = fields.last().map(Field::end).unwrap_or(0);
+        if decoded_to < data.len() {
+            notes.push(format!("Decoded {} of {} bytes from IDL '{}'", decoded_to, data.len(), self.idl.name));
+        }
+        Some(DecodedAccount { decoder: self.name().to_string(), type_name: name.to_string(), fields, notes })
+    }
+}
+
+/// SPL Token and Token-2022 mints, token accounts and multisigs
+pub struct TokenDecoder;
+
+impl AccountDecoder for TokenDecoder {
+    fn name(&self) -> &str {
+        "SPL Token"
+    }
+
+    fn matches(&self, _data: &[u8], owner: &Pubkey) -> bool {
+        token::is_token_program(owner)
+    }
+
+    fn decode(&self, data: &[u8], _owner: &Pubkey) -> Option<DecodedAccount> {
+        let account = token::decode(data)?;
+        Some(DecodedAccount {
+            decoder: self.name().to_string(),
+            type_name: account.label(),
+            fields: account.fields,
+            notes: Vec::new(),
+        })
+    }
+}
+
+/// Metaplex Token Metadata accounts
+pub struct MetaplexDecoder;
+
+impl AccountDecoder for MetaplexDecoder {
+    fn name(&self) -> &str {
+        "Metaplex Token Metadata"
+    }
+
+    fn matches(&self, _data: &[u8], owner: &Pubkey) -> bool {
+        *owner == metaplex::TOKEN_METADATA_PROGRAM_ID
+    }
+
+    fn decode(&self, data: &[u8], _owner: &Pubkey) -> Option<DecodedAccount> {
+        let account = metaplex::decode(data)?;
+        Some(DecodedAccount {
+            decoder: self.name().to_string(),
+            type_name: account.kind,
+            fields: account.fields,
+            notes: Vec::new(),
+        })
+    }
+}
+
+/// Candy Machine v3 and Candy Guard accounts
+pub struct CandyDecoder;
+
+impl AccountDecoder for CandyDecoder {
+    fn name(&self) -> &str {
+        "Candy Machine"
+    }
+
+    fn matches(&self, _data: &[u8], owner: &Pubkey) -> bool {
+        candy::is_candy_program(owner)
+    }
+
+    fn decode(&self, data: &[u8], _owner: &Pubkey) -> Option<DecodedAccount> {
+        let account = candy::decode(data)?;
+        Some(DecodedAccount {
+            decoder: self.name().to_string(),
+            type_name: account.kind.to_string(),
+            fields: account.fields,
+            notes: account.notes,
+        })
+    }
+}
+
+/// Nonce, stake, vote, address lookup table and config accounts
+pub struct NativeDecoder;
+
+impl AccountDecoder for NativeDecoder {
+    fn name(&self) -> &str {
+        "Native"
+    }
+
+    fn matches(&self, _data: &[u8], owner: &Pubkey) -> bool {
+        native::is_native_program(owner)
+    }
+
+    fn decode(&self, data: &[u8], owner: &Pubkey) -> Option<DecodedAccount> {
+        let account = native::decode(data, owner)?;
+        Some(DecodedAccount {
+            decoder: self.name().to_string(),
+            type_name: account.kind,
+            fields: account.fields,
+            notes: account.notes,
+        })
+    }
+}
+
+/// Parses every `*.json` file in `dir` as an Anchor IDL, in file name order
+fn load_idl_dir(dir: &str) -> Result<Vec<Idl>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| anyhow!("Failed to read decoder directory {}: {}", dir, e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    paths.iter().map(|path| Idl::load(&path.to_string_lossy())).collect()
+}
+
+/// Registers the decoders of a program embedding SPADE, see `crate::run_with_decoders`
+static CUSTOM_DECODERS: OnceLock<fn(&mut DecoderRegistry)> = OnceLock::new();
+
+/// Sets the function `with_builtin` calls to add custom decoders. Only the first call counts
+pub fn set_custom_decoders(register: fn(&mut DecoderRegistry)) {
+    let _ = CUSTOM_DECODERS.set(register);
+}
+
+/// Decoders tried in registration order; the first that matches and decodes wins
+#[derive(Default)]
+pub struct DecoderRegistry {
+    decoders: Vec<Box<dyn AccountDecoder>>,
+}
+
+impl DecoderRegistry {
+    /// A decoder per loaded IDL, then the custom decoders, then the built-in program decoders
+    pub fn with_builtin(idls: impl IntoIterator<Item = Idl>) -> Self {
+        let mut registry = Self::default();
+        for idl in idls {
+            registry.register(IdlDecoder { idl });
+        }
+        if let Some(register) = CUSTOM_DECODERS.get() {
+            register(&mut registry);
+        }
+        registry.register(TokenDecoder);
+        registry.register(MetaplexDecoder);
+        registry.register(CandyDecoder);
+        registry.register(NativeDecoder);
+        registry
+    }
+
+    /// Built-in decoders plus the IDL given with `--idl FILE` or SPADE_IDL, and every `*.json`
+    /// IDL in the directory given with `--decoders DIR` or SPADE_DECODERS
+    pub fn from_args(args: &[String]) -> Result<Self> {
+        let mut idls: Vec<Idl> = idl::from_args(args)?.into_iter().collect();
+        let dir = filters::flag_values(args, "--decoders")
+            .into_iter()
+            .next()
+            .or_else(|| std::env::var("SPADE_DECODERS").ok());
+        if let Some(dir) = dir {
+            for idl in load_idl_dir(&dir)? {
+                println!("Loaded decoder IDL '{}': {} accounts", idl.name, idl.accounts.len());
+                idls.push(idl);
+            }
+        }
+        Ok(Self::with_builtin(idls))
+    }
+
+    pub fn register(&mut self, decoder: impl AccountDecoder + 'static) {
+        self.decoders.push(Box::new(decoder));
+    }
+
+    pub fn decode(&self, data: &[u8], owner: &Pubkey) -> Option<DecodedAccount> {
+        self.decoders.iter()
+            .filter(|decoder| decoder.matches(data, owner))
+            .find_map(|decoder| decoder.decode(data, owner))
+    }
+
+    /// Decoded fields for the hexdump and diffs, falling back to the inferred layout
+    pub fn layout(&self, data: &[u8], owner: &Pubkey, program_accounts: &HashSet<Pubkey>) -> Vec<Field> {
+        self.decode(data, owner)
+            .map(|decoded| decoded.fields)
+            .unwrap_or_else(|| layout::infer_layout(data, owner, program_accounts))
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::decoder::DecoderRegistry;
+use crate::layout::{read_u64, Field};
+use crate::snapshot::{Snapshot, SnapshotAccount};
+
+/// Equal bytes allowed inside one changed range before it is split in two
+const MERGE_GAP: usize = 4;
+
+/// How many bytes of a changed range are printed as hex
+const MAX_HEX_BYTES: usize = 32;
+
+/// Returns the byte ranges (start, end) that differ between two buffers, including the bytes
+/// only one of them has when their lengths differ
+pub fn changed_ranges(before: &[u8], after: &[u8]) -> Vec<(usize, usize)> {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+
+    for i in 0..before.len().min(after.len()) {
+        if before[i] == after[i] {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some((_, end)) if i - *end <= MERGE_GAP => *end = i + 1,
+            _ => ranges.push((i, i + 1)),
+        }
+    }
+
+    let (min_len, max_len) = (before.len().min(after.len()), before.len().max(after.len()));
+    if min_len < max_len {
+        match ranges.last_mut() {
+            Some((_, end)) if min_len - *end <= MERGE_GAP => *end = max_len,
+            _ => ranges.push((min_len, max_len)),
+        }
+    }
+
+    ranges
+}
+
+/// Hex of up to `MAX_HEX_BYTES` of `data[start..end]`, `-` if the buffer ends before `start`
+fn hex_excerpt(data: &[u8], start: usize, end: usize) -> String {
+    let bytes = &data[start.min(data.len())..end.min(data.len())];
+    match bytes.len() {
+        0 => "-".to_string(),
+        len if len > MAX_HEX_BYTES => format!("{}…", hex::encode(&bytes[..MAX_HEX_BYTES])),
+        _ => hex::encode(bytes),
+    }
+}
+
+/// Formats a snapshot's capture time and slot for the report header
+fn describe_snapshot(path: &str, snapshot: &Snapshot) -> String {
+    let time = DateTime::<Utc>::from_timestamp(snapshot.captured_at, 0)
+        .unwrap_or_default()
+        .format("%Y-%m-%d %H:%M:%S");
+    format!("{} (slot {}, {}, {} accounts)", path, snapshot.slot, time, snapshot.accounts.len())
+}
+
+/// Fields from either layout that overlap one of the changed ranges
+fn changed_fields<'a>(fields: &'a [Field], ranges: &[(usize, usize)]) -> Vec<&'a Field> {
+    fields.iter()
+        .filter(|f| ranges.iter().any(|&(start, end)| f.offset < end && start < f.end()))
+        .collect()
+}
+
+/// Prints the lamport, size, byte range and field changes between two versions of an account
+pub fn print_changes(
+    before: &SnapshotAccount,
+    after: &SnapshotAccount,
+    before_layout: &[Field],
+    after_layout: &[Field],
+) {
+    if before.lamports != after.lamports {
+        println!("   lamports: {} → {} ({:+})",
+            before.lamports, after.lamports, after.lamports as i128 - before.lamports as i128);
+    }
+    if before.data.len() != after.data.len() {
+        println!("   size: {} → {} bytes", before.data.len(), after.data.len());
+    }
+
+    let ranges = changed_ranges(&before.data, &after.data);
+    for &(start, end) in &ranges {
+        println!("   [{:#06x}..{:#06x}] {} bytes: {} → {}",
+            start, end, end - start,
+            hex_excerpt(&before.data, start, end), hex_excerpt(&after.data, start, end));
+    }
+
+    let old_fields = changed_fields(before_layout, &ranges);
+    let new_fields = changed_fields(after_layout, &ranges);
+
+    // Small changes outside any known field are most often integers, show them as u64
+    for &(start, end) in &ranges {
+        let covered = old_fields.iter().chain(&new_fields).any(|f| f.offset < end && start < f.end());
+        let word = start - start % 8;
+        if covered || end > word + 8 {
+            continue;
+        }
+        if let (Some(old), Some(new)) = (read_u64(&before.data, word), read_u64(&after.data, word)) {
+            println!("   +{:#06x} as u64: {} → {}", word, old, new);
+        }
+    }
+
+    if old_fields.is_empty() && new_fields.is_empty() {
+        return;
+    }
+
+    println!("   Changed fields:");
+    let mut offsets: Vec<usize> = old_fields.iter().chain(&new_fields).map(|f| f.offset).collect();
+    offsets.sort_unstable();
+    offsets.dedup();
+    for offset in offsets {
+        let old = old_fields.iter().find(|f| f.offset == offset);
+        let new = new_fields.iter().find(|f| f.offset == offset);
+        let name = old.or(new).map(|f| f.name.as_str()).unwrap_or_default();
+        println!("     +{:#06x} {}: {} → {}",
+            offset, name,
+            old.map(|f| f.value.as_str()).unwrap_or("-"),
+            new.map(|f| f.value.as_str()).unwrap_or("-"));
+    }
+}
+
+/// Compares two snapshots and reports created, closed, resized and modified accounts
+pub fn diff_snapshots(before_path: &str, after_path: &str, args: &[String]) -> Result<()> {
+    let before = Snapshot::load(before_path)?;
+    let after = Snapshot::load(after_path)?;
+
+    if before.program_id != after.program_id {
+        bail!("Snapshots are for different programs ({} vs {})", before.program_id, after.program_id);
+    }
+    let registry = DecoderRegistry::from_args(args)?;
+
+    println!("\n🔀 SNAPSHOT DIFF");
+    println!("===============");
+    println!("Program: {}", before.program_id);
+    println!("Before:  {}", describe_snapshot(before_path, &before));
+    println!("After:   {}", describe_snapshot(after_path, &after));
+
+    let before_map: HashMap<Pubkey, &SnapshotAccount> = before.accounts.iter().map(|a| (a.pubkey, a)).collect();
+    let after_map: HashMap<Pubkey, &SnapshotAccount> = after.accounts.iter().map(|a| (a.pubkey, a)).collect();
+    let before_keys: HashSet<Pubkey> = before_map.keys().copied().collect();
+    let after_keys: HashSet<Pubkey> = after_map.keys().copied().collect();
+
+    let mut created: Vec<_> = after.accounts.iter().filter(|a| !before_map.contains_key(&a.pubkey)).collect();
+    let mut closed: Vec<_> = before.accounts.iter().filter(|a| !after_map.contains_key(&a.pubkey)).collect();
+    created.sort_by_key(|a| a.pubkey);
+    closed.sort_by_key(|a| a.pubkey);
+
+    let mut modified = Vec::new();
+    let mut resized = 0;
+    for account in &after.accounts {
+        if let Some(old) = before_map.get(&account.pubkey) {
+            if old.data != account.data || old.lamports != account.lamports {
+                if old.data.len() != account.data.len() {
+                    resized += 1;
+                }
+                modified.push((*old, account));
+            }
+        }
+    }
+
+    println!("\n📊 Summary:");
+    println!("- Created:   {}", created.len());
+    println!("- Closed:    {}", closed.len());
+    println!("- Modified:  {} ({} resized)", modified.len(), resized);
+    println!("- Unchanged: {}", after.accounts.len() - created.len() - modified.len());
+
+    if !created.is_empty() {
+        println!("\n🆕 Created accounts:");
+        for account in &created {
+            println!("  + {} ({} bytes, {} lamports)", account.pubkey, account.data.len(), account.lamports);
+        }
+    }
+
+    if !closed.is_empty() {
+        println!("\n🗑️  Closed accounts:");
+        for account in &closed {
+            println!("  - {} ({} bytes, {} lamports)", account.pubkey, account.data.len(), account.lamports);
+        }
+    }
+
+    if !modified.is_empty() {
+        println!("\n✏️  Modified accounts:");
+        for (old, new) in modified {
+            let before_layout = registry.layout(&old.data, &before.program_id, &before_keys);
+            let after_layout = registry.layout(&new.data, &after.program_id, &after_keys);
+            println!("\n✏️  {}", new.pubkey);
+            print_changes(old, new, &before_layout, &after_layout);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_small_gaps() {
+        let before = [0u8; 16];
+        let mut after = before;
+        after[1] = 1;
+        after[6] = 1;
+        after[12] = 1;
+        assert_eq!(changed_ranges(&before, &after), [(1, 7), (12, 13)]);
+        assert!(changed_ranges(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn grow_and_shrink_add_a_tail_range() {
+        let short = [1u8, 2, 3, 4];
+        let long = [1u8, 2, 3, 4, 5, 6];
+        assert_eq!(changed_ranges(&short, &long), [(4, 6)]);
+        assert_eq!(changed_ranges(&long, &short), [(4, 6)]);
+
+        let long = [1u8, 9, 3, 4, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(changed_ranges(&short, &long), [(1, 12)]);
+    }
+
+    #[test]
+    fn excerpt_past_the_end() {
+        assert_eq!(hex_excerpt(&[1, 2], 2, 4), "-");
+        assert_eq!(hex_excerpt(&[1, 2, 3], 1, 4), "0203");
+        assert_eq!(hex_excerpt(&[0; 40], 0, 40), format!("{}…", "00".repeat(32)));
+    }
+}
@@ -0,0 +1,505 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::fs;
+
+use anyhow::{anyhow, Result};
+use goblin::elf::Elf;
+use rustc_demangle::demangle;
+
+use crate::filters;
+use crate::program::{self, ProgramInfo};
+use crate::AccountFetcher;
+
+/// Bytes per sBPF instruction slot (`lddw` takes two)
+const INSN_SIZE: usize = 8;
+
+/// Virtual address the runtime maps the program's read-only sections to
+const MM_PROGRAM_START: u64 = 0x1_0000_0000;
+
+/// Relocation types of the sBPF toolchain
+const R_BPF_64_32: u32 = 10;
+
+/// Longest string shown for a string reference
+const MAX_STRING_LEN: usize = 80;
+
+/// Syscalls of the Solana runtime, resolved by name or by the murmur3 hash in `call imm`
+const SYSCALLS: &[&str] = &[
+    "abort",
+    "sol_panic_",
+    "sol_log_",
+    "sol_log_64_",
+    "sol_log_compute_units_",
+    "sol_log_pubkey",
+    "sol_log_data",
+    "sol_create_program_address",
+    "sol_try_find_program_address",
+    "sol_sha256",
+    "sol_keccak256",
+    "sol_blake3",
+    "sol_secp256k1_recover",
+    "sol_poseidon",
+    "sol_big_mod_exp",
+    "sol_curve_validate_point",
+    "sol_curve_group_op",
+    "sol_curve_multiscalar_mul",
+    "sol_alt_bn128_group_op",
+    "sol_alt_bn128_compression",
+    "sol_get_clock_sysvar",
+    "sol_get_epoch_schedule_sysvar",
+    "sol_get_fees_sysvar",
+    "sol_get_rent_sysvar",
+    "sol_get_epoch_rewards_sysvar",
+    "sol_get_last_restart_slot",
+    "sol_get_sysvar",
+    "sol_get_epoch_stake",
+    "sol_memcpy_",
+    "sol_memmove_",
+    "sol_memcmp_",
+    "sol_memset_",
+    "sol_invoke_signed_c",
+    "sol_invoke_signed_rust",
+    "sol_set_return_data",
+    "sol_get_return_data",
+    "sol_get_processed_sibling_instruction",
+    "sol_get_stack_height",
+    "sol_remaining_compute_units",
+];
+
+/// murmur3 (32-bit, seed 0), the hash the runtime uses for syscall ids in `call imm`
+fn murmur3_32(data: &[u8]) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+    let mix = |k: u32| k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+
+    let mut hash = 0u32;
+    let chunks = data.chunks_exact(4);
+    let tail = chunks.remainder();
+    for chunk in chunks {
+        hash ^= mix(u32::from_le_bytes(chunk.try_into().unwrap()));
+        hash = hash.rotate_left(13).wrapping_mul(5).wrapping_add(0xe654_6b64);
+    }
+    if !tail.is_empty() {
+        let k = tail.iter().rev().fold(0u32, |k, &b| (k << 8) | b as u32);
+        hash ^= mix(k);
+    }
+
+    hash ^= data.len() as u32;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2_ae35);
+    hash ^ (hash >> 16)
+}
+
+/// One decoded instruction
+#[derive(Debug, Clone, Copy)]
+pub struct Insn {
+    /// Index of the instruction's first slot in .text
+    pub pc: usize,
+    pub opcode: u8,
+    pub dst: u8,
+    pub src: u8,
+    pub off: i16,
+    /// Sign-extended immediate, or the full 64-bit value for `lddw`
+    pub imm: i64,
+}
+
+impl Insn {
+    fn slots(&self) -> usize {
+        if self.opcode == 0x18 { 2 } else { 1 }
+    }
+
+    /// Target of a jump, as an instruction index
+    fn jump_target(&self) -> usize {
+        (self.pc as i64 + 1 + self.off as i64) as usize
+    }
+}
+
+/// Hex with the sign in front, `-0x8` rather than the two's complement `0xfffffffffffffff8`
+fn signed_hex(value: i64) -> String {
+    if value < 0 { format!("-{:#x}", value.unsigned_abs()) } else { format!("{:#x}", value) }
+}
+
+/// A memory operand offset, `+0x8` or `-0x8`
+fn offset_hex(off: i16) -> String {
+    if off < 0 { format!("-{:#x}", off.unsigned_abs()) } else { format!("+{:#x}", off) }
+}
+
+/// Slot a relative `call imm` lands on (pc + 1 + imm), unless that is negative or overflows.
+/// `call -1` is how unresolved calls are emitted, so it never counts as relative
+fn relative_call_target(insn: &Insn) -> Option<usize> {
+    if insn.imm == -1 {
+        return None;
+    }
+    insn.pc.checked_add(1)?.checked_add_signed(isize::try_from(insn.imm).ok()?)
+}
+
+/// Decodes .text into instructions
+fn decode(text: &[u8]) -> Vec<Insn> {
+    let mut insns = Vec::with_capacity(text.len() / INSN_SIZE);
+    let mut pc = 0;
+    while let Some(slot) = text.get(pc * INSN_SIZE..(pc + 1) * INSN_SIZE) {
+        let mut insn = Insn {
+            pc,
+            opcode: slot[0],
+            dst: slot[1] & 0x0f,
+            src: slot[1] >> 4,
+            off: i16::from_le_bytes([slot[2], slot[3]]),
+            imm: i32::from_le_bytes(slot[4..8].try_into().unwrap()) as i64,
+        };
+        if insn.opcode == 0x18 {
+            let high = text.get((pc + 1) * INSN_SIZE + 4..(pc + 2) * INSN_SIZE)
+                .map_or(0, |b| u32::from_le_bytes(b.try_into().unwrap()));
+            insn.imm = ((high as u64) << 32 | (insn.imm as u32 as u64)) as i64;
+        }
+        pc += insn.slots();
+        insns.push(insn);
+    }
+    insns
+}
+
+/// What a `call` instruction invokes
+#[derive(Debug, Clone)]
+enum CallTarget {
+    Syscall(String),
+    Function(usize),
+    Unknown(i64),
+}
+
+/// A disassembled program: instructions, functions, calls and string references
+pub struct Disassembly {
+    pub insns: Vec<Insn>,
+    /// Function start (instruction index) → name
+    pub functions: BTreeMap<usize, String>,
+    calls: HashMap<usize, CallTarget>,
+    /// Instruction index → referenced string
+    pub strings: BTreeMap<usize, String>,
+    pub entrypoint: Option<usize>,
+    pub text_len: usize,
+}
+
+/// Reads a printable string at `addr` from the read-only sections, `len` bytes if known
+fn read_string(readonly: &[(u64, &[u8])], addr: u64, len: Option<usize>) -> Option<String> {
+    let addr = if addr >= MM_PROGRAM_START { addr - MM_PROGRAM_START } else { addr };
+    let (start, bytes) = readonly.iter().find(|(start, bytes)| addr >= *start && addr < start + bytes.len() as u64)?;
+    let bytes = &bytes[(addr - start) as usize..];
+    let max = len.unwrap_or(MAX_STRING_LEN).min(MAX_STRING_LEN).min(bytes.len());
+    let printable = bytes[..max].iter()
+        .take_while(|b| b.is_ascii_graphic() || matches!(b, b' ' | b'\n' | b'\t'))
+        .count();
+    // Without a known length, require a few printable bytes to call it a string
+    if printable == 0 || (len.is_none() && printable < 4) {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&bytes[..printable]).escape_debug().to_string();
+    Some(if len.is_some_and(|len| len > printable) { format!("{}…", text) } else { text })
+}
+
+impl Disassembly {
+    /// Disassembles the .text section of an sBPF ELF
+    pub fn new(elf: &[u8]) -> Result<Self> {
+        let parsed = Elf::parse(elf).map_err(|e| anyhow!("Not a valid ELF: {}", e))?;
+        let section_name = |sh: &goblin::elf::SectionHeader| parsed.shdr_strtab.get_at(sh.sh_name).unwrap_or("");
+        let text_header = parsed.section_headers.iter()
+            .find(|sh| section_name(sh) == ".text")
+            .ok_or_else(|| anyhow!("ELF has no .text section"))?;
+        let text = elf.get(text_header.file_range().unwrap_or_default())
+            .ok_or_else(|| anyhow!(".text is out of bounds"))?;
+        let text_addr = text_header.sh_addr;
+        let index_of = |addr: u64| addr.checked_sub(text_addr).map(|offset| offset as usize / INSN_SIZE);
+
+        let readonly: Vec<(u64, &[u8])> = parsed.section_headers.iter()
+            .filter(|sh| matches!(section_name(sh), ".rodata" | ".data.rel.ro"))
+            .filter_map(|sh| Some((sh.sh_addr, elf.get(sh.file_range()?)?)))
+            .collect();
+
+        let insns = decode(text);
+        let syscall_hashes: HashMap<u32, &str> = SYSCALLS.iter().map(|name| (murmur3_32(name.as_bytes()), *name)).collect();
+
+        let mut functions = BTreeMap::new();
+        let entrypoint = index_of(parsed.entry);
+        if let Some(entry) = entrypoint {
+            functions.insert(entry, "entrypoint".to_string());
+        }
+        let symbols = parsed.syms.iter().map(|sym| (sym, &parsed.strtab))
+            .chain(parsed.dynsyms.iter().map(|sym| (sym, &parsed.dynstrtab)));
+        for (sym, strtab) in symbols.filter(|(sym, _)| sym.is_function() && sym.st_value != 0) {
+            let name = strtab.get_at(sym.st_name).unwrap_or_default();
+            if let Some(index) = index_of(sym.st_value).filter(|_| !name.is_empty()) {
+                functions.entry(index).or_insert_with(|| format!("{:#}", demangle(name)));
+            }
+        }
+
+        // Calls resolved by relocations: defined symbols are internal functions, others syscalls
+        let mut calls = HashMap::new();
+        for reloc in parsed.dynrels.iter().filter(|r| r.r_type == R_BPF_64_32) {
+            let Some(pc) = index_of(reloc.r_offset) else { continue };
+            let Some(sym) = parsed.dynsyms.get(reloc.r_sym) else { continue };
+            let name = parsed.dynstrtab.get_at(sym.st_name).unwrap_or_default();
+            let target = match index_of(sym.st_value).filter(|_| sym.st_value != 0) {
+                Some(index) => CallTarget::Function(index),
+                None => CallTarget::Syscall(name.to_string()),
+            };
+            calls.insert(pc, target);
+        }
+
+        let pc_to_slot: HashMap<usize, usize> = insns.iter().enumerate().map(|(i, insn)| (insn.pc, i)).collect();
+        let mut strings = BTreeMap::new();
+        for (i, insn) in insns.iter().enumerate() {
+            if insn.opcode == 0x85 && !calls.contains_key(&insn.pc) {
+                let target = match syscall_hashes.get(&(insn.imm as u32)) {
+                    Some(name) => CallTarget::Syscall(name.to_string()),
+                    // Relative calls (newer toolchains) land on an instruction boundary
+                    None => match relative_call_target(insn) {
+                        Some(target) if pc_to_slot.contains_key(&target) => CallTarget::Function(target),
+                        _ => CallTarget::Unknown(insn.imm),
+                    },
+                };
+                calls.insert(insn.pc, target);
+            }
+
+            // `lddw rX, addr` followed by `mov rX+1, len` is how Rust passes a &str
+            if insn.opcode == 0x18 {
+                let len = insns.get(i + 1)
+                    .filter(|next| next.opcode == 0xb7 && next.dst == insn.dst + 1 && (1..4096).contains(&next.imm))
+                    .map(|next| next.imm as usize);
+                if let Some(text) = read_string(&readonly, insn.imm as u64, len) {
+                    strings.insert(insn.pc, text);
+                }
+            }
+        }
+
+        for target in calls.values() {
+            if let CallTarget::Function(index) = target {
+                functions.entry(*index).or_insert_with(|| format!("function_{}", index));
+            }
+        }
+
+        Ok(Self { insns, functions, calls, strings, entrypoint, text_len: text.len() })
+    }
+
+    /// The function containing an instruction
+    fn function_of(&self, pc: usize) -> Option<(&usize, &String)> {
+        self.functions.range(..=pc).next_back()
+    }
+
+    fn call_name(&self, pc: usize) -> String {
+        match self.calls.get(&pc) {
+            Some(CallTarget::Syscall(name)) => name.clone(),
+            Some(CallTarget::Function(index)) => self.functions.get(index).cloned().unwrap_or_else(|| format!("function_{}", index)),
+            Some(CallTarget::Unknown(imm)) => signed_hex(*imm),
+            None => "?".to_string(),
+        }
+    }
+
+    /// Formats one instruction in the usual sBPF assembly syntax
+    pub fn format(&self, insn: &Insn) -> String {
+        const ALU: [&str; 14] = ["add", "sub", "mul", "div", "or", "and", "lsh", "rsh", "neg", "mod", "xor", "mov", "arsh", "end"];
+        const JMP: [&str; 14] = ["ja", "jeq", "jgt", "jge", "jset", "jne", "jsgt", "jsge", "call", "exit", "jlt", "jle", "jslt", "jsle"];
+        let size = |op: u8| match op & 0x18 {
+            0x00 => "w",
+            0x08 => "h",
+            0x10 => "b",
+            _ => "dw",
+        };
+        let (dst, src, off) = (insn.dst, insn.src, insn.off);
+        let operand = |op: u8| if op & 0x08 != 0 { format!("r{}", src) } else { signed_hex(insn.imm) };
+
+        match insn.opcode & 0x07 {
+            0x00 if insn.opcode == 0x18 => format!("lddw r{}, {}", dst, signed_hex(insn.imm)),
+            0x01 => format!("ldx{} r{}, [r{}{}]", size(insn.opcode), dst, src, offset_hex(off)),
+            0x02 => format!("st{} [r{}{}], {}", size(insn.opcode), dst, offset_hex(off), signed_hex(insn.imm)),
+            0x03 => format!("stx{} [r{}{}], r{}", size(insn.opcode), dst, offset_hex(off), src),
+            class @ (0x04 | 0x07) => {
+                let bits = if class == 0x04 { "32" } else { "64" };
+                match ALU.get((insn.opcode >> 4) as usize) {
+                    Some(&"neg") => format!("neg{} r{}", bits, dst),
+                    Some(&"end") => format!("{}{} r{}", if insn.opcode & 0x08 != 0 { "be" } else { "le" }, insn.imm, dst),
+                    Some(op) => format!("{}{} r{}, {}", op, bits, dst, operand(insn.opcode)),
+                    None => format!("unknown {:#04x}", insn.opcode),
+                }
+            }
+            0x05 => match insn.opcode {
+                0x05 => format!("ja lbb_{}", insn.jump_target()),
+                0x85 => format!("call {}", self.call_name(insn.pc)),
+                0x8d => format!("callx r{}", insn.imm),
+                0x95 => "exit".to_string(),
+                op => match JMP.get((op >> 4) as usize) {
+                    Some(name) => format!("{} r{}, {}, lbb_{}", name, dst, operand(op), insn.jump_target()),
+                    None => format!("unknown {:#04x}", op),
+                },
+            },
+            _ => format!("unknown {:#04x}", insn.opcode),
+        }
+    }
+
+    /// Full listing with function headers, jump labels and string comments
+    pub fn listing(&self) -> String {
+        let labels: std::collections::HashSet<usize> = self.insns.iter()
+            .filter(|insn| insn.opcode & 0x07 == 0x05 && !matches!(insn.opcode, 0x85 | 0x8d | 0x95))
+            .map(Insn::jump_target)
+            .collect();
+
+        let mut out = String::new();
+        for insn in &self.insns {
+            if let Some(name) = self.functions.get(&insn.pc) {
+                let _ = writeln!(out, "\n{}:", name);
+            } else if labels.contains(&insn.pc) {
+                let _ = writeln!(out, "lbb_{}:", insn.pc);
+            }
+            let _ = write!(out, "  {:>6x}:  {}", insn.pc * INSN_SIZE, self.format(insn));
+            if let Some(text) = self.strings.get(&insn.pc) {
+                let _ = write!(out, "    ; \"{}\"", text);
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Disassembles the deployed program (or `--so FILE`), prints an overview of functions,
+/// syscalls and string references and writes the listing to `--out FILE`
+pub fn disassemble_program(fetcher: &mut AccountFetcher, args: &[String]) -> Result<()> {
+    let elf = match filters::flag_values(args, "--so").first() {
+        Some(path) => fs::read(path).map(|elf| program::trim_padding(&elf).to_vec())?,
+        None => ProgramInfo::fetch(fetcher)?.elf,
+    };
+    let out = filters::flag_values(args, "--out").into_iter().next()
+        .unwrap_or_else(|| format!("{}.asm", fetcher.program_id));
+
+    println!("\n🛠️  sBPF DISASSEMBLY");
+    println!("===================");
+
+    let disasm = Disassembly::new(&elf)?;
+    println!(".text: {} bytes, {} instructions, {} functions",
+        disasm.text_len, disasm.insns.len(), disasm.functions.len());
+    if let Some(entry) = disasm.entrypoint {
+        println!("Entrypoint: {:#x}", entry * INSN_SIZE);
+    }
+
+    // Syscalls by call count
+    let mut syscalls: HashMap<&str, usize> = HashMap::new();
+    let mut callers: HashMap<usize, usize> = HashMap::new();
+    for target in disasm.calls.values() {
+        match target {
+            CallTarget::Syscall(name) => *syscalls.entry(name).or_default() += 1,
+            CallTarget::Function(index) => *callers.entry(*index).or_default() += 1,
+            CallTarget::Unknown(_) => {}
+        }
+    }
+    let mut syscalls: Vec<_> = syscalls.into_iter().collect();
+    syscalls.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    println!("\n📞 Syscalls ({}):", syscalls.len());
+    for (name, count) in &syscalls {
+        println!("  {:<40} {:>6} call sites", name, count);
+    }
+
+    // Instruction handlers: functions referencing Anchor's `Instruction: <Name>` log strings
+    let handlers: Vec<_> = disasm.strings.iter()
+        .filter_map(|(pc, text)| Some((text.strip_prefix("Instruction: ")?, disasm.function_of(*pc)?)))
+        .collect();
+    if !handlers.is_empty() {
+        println!("\n🚪 Instruction entrypoints:");
+        for (name, (start, function)) in handlers {
+            println!("  {:<32} in {} ({:#x})", name, function, start * INSN_SIZE);
+        }
+    }
+
+    // Largest and most called functions
+    let starts: Vec<usize> = disasm.functions.keys().copied().collect();
+    let index_of = |pc: usize| disasm.insns.partition_point(|insn| insn.pc < pc);
+    let mut sized: Vec<_> = starts.iter().enumerate()
+        .map(|(i, start)| {
+            let end = starts.get(i + 1).map_or(disasm.insns.len(), |next| index_of(*next));
+            (*start, end - index_of(*start))
+        })
+        .collect();
+    sized.sort_by_key(|(_, len)| std::cmp::Reverse(*len));
+    println!("\n🧱 Largest functions:");
+    for (start, len) in sized.iter().take(15) {
+        println!("  {:<40} {:>8x} {:>7} insns  {:>4} callers",
+            disasm.functions[start], start * INSN_SIZE, len, callers.get(start).copied().unwrap_or(0));
+    }
+
+    println!("\n🔤 String references ({}):", disasm.strings.len());
+    for (pc, text) in disasm.strings.iter().take(40) {
+        let function = disasm.function_of(*pc).map(|(_, name)| name.as_str()).unwrap_or("?");
+        println!("  {:>8x}  {:<32} \"{}\"", pc * INSN_SIZE, function, text);
+    }
+    if disasm.strings.len() > 40 {
+        println!("  ... {} more in the listing", disasm.strings.len() - 40);
+    }
+
+    fs::write(&out, disasm.listing())?;
+    println!("\nFull listing written to {}", out);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Formats raw instruction bytes, with `calls` resolved as given
+    fn format_all(text: &[u8], calls: &[(usize, CallTarget)]) -> Vec<String> {
+        let disassembly = Disassembly {
+            insns: decode(text),
+            functions: BTreeMap::new(),
+            calls: calls.iter().cloned().collect(),
+            strings: BTreeMap::new(),
+            entrypoint: None,
+            text_len: text.len(),
+        };
+        disassembly.insns.iter().map(|insn| disassembly.format(insn)).collect()
+    }
+
+    #[test]
+    fn formats_signed_offsets_and_immediates() {
+        let text = [
+            0x79, 0xa1, 0xf8, 0xff, 0x00, 0x00, 0x00, 0x00, // ldxdw r1, [r10-0x8]
+            0xb7, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, // mov64 r0, -1
+            0x7b, 0x1a, 0xf0, 0xff, 0x00, 0x00, 0x00, 0x00, // stxdw [r10-0x10], r1
+            0x62, 0x01, 0x04, 0x00, 0xfe, 0xff, 0xff, 0xff, // stw [r1+0x4], -2
+            0x15, 0x01, 0x01, 0x00, 0x05, 0x00, 0x00, 0x00, // jeq r1, 5, +1
+            0x85, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, // call -1
+            0x95, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // exit
+        ];
+        assert_eq!(format_all(&text, &[(5, CallTarget::Unknown(-1))]), [
+            "ldxdw r1, [r10-0x8]",
+            "mov64 r0, -0x1",
+            "stxdw [r10-0x10], r1",
+            "stw [r1+0x4], -0x2",
+            "jeq r1, 0x5, lbb_6",
+            "call -0x1",
+            "exit",
+        ]);
+    }
+
+    #[test]
+    fn decodes_lddw_across_two_slots() {
+        let text = [
+            0x18, 0x01, 0x00, 0x00, 0x78, 0x56, 0x34, 0x12,
+            0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+            0x85, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let insns = decode(&text);
+        assert_eq!(insns.iter().map(|insn| insn.pc).collect::<Vec<_>>(), [0, 2]);
+        assert_eq!(format_all(&text, &[(2, CallTarget::Syscall("sol_log_".to_string()))]),
+            ["lddw r1, 0x112345678", "call sol_log_"]);
+    }
+
+    #[test]
+    fn syscall_hashes() {
+        assert_eq!(murmur3_32(b"sol_log_"), 0x207559bd);
+        assert_eq!(murmur3_32(b"abort"), 0xb6fc1a11);
+    }
+
+    #[test]
+    fn resolves_relative_calls_inside_the_program() {
+        let call = |pc, imm| Insn { pc, opcode: 0x85, dst: 0, src: 0, off: 0, imm };
+        assert_eq!(relative_call_target(&call(4, 3)), Some(8));
+        assert_eq!(relative_call_target(&call(4, -5)), Some(0));
+        assert_eq!(relative_call_target(&call(4, -6)), None);
+        assert_eq!(relative_call_target(&call(4, -1)), None);
+        assert_eq!(relative_call_target(&call(usize::MAX, 0)), None);
+    }
+}
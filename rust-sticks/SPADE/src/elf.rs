@@ -0,0 +1,168 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use goblin::elf::Elf;
+use regex::bytes::Regex;
+
+/// Markers solana-security-txt places around the embedded fields
+const SECURITY_TXT_BEGIN: &[u8] = b"=======BEGIN SECURITY.TXT V1=======\0";
+const SECURITY_TXT_END: &[u8] = b"=======END SECURITY.TXT V1=======\0";
+
+/// Instructions Anchor adds to every program for on-chain IDL management
+const ANCHOR_IDL_INSTRUCTIONS: &[&str] = &[
+    "IdlCreateAccount",
+    "IdlResizeAccount",
+    "IdlCloseAccount",
+    "IdlCreateBuffer",
+    "IdlWrite",
+    "IdlSetBuffer",
+    "IdlSetAuthority",
+];
+
+/// Metadata embedded in a program binary
+#[derive(Debug, Default)]
+pub struct EmbeddedMetadata {
+    /// security.txt (key, value) pairs in file order
+    pub security_txt: Vec<(String, String)>,
+    /// Crate name → versions, from the registry paths compiled into panic messages
+    pub crate_versions: BTreeMap<String, BTreeSet<String>>,
+    /// Anchor instruction names from `Instruction: <Name>` log strings
+    pub instruction_names: BTreeSet<String>,
+}
+
+/// Finds `needle` in `haystack`
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// Parses the null-separated key/value pairs between the security.txt markers
+pub fn security_txt(elf: &[u8]) -> Vec<(String, String)> {
+    let Some(begin) = find(elf, SECURITY_TXT_BEGIN).map(|i| i + SECURITY_TXT_BEGIN.len()) else {
+        return Vec::new();
+    };
+    let Some(len) = find(&elf[begin..], SECURITY_TXT_END) else {
+        return Vec::new();
+    };
+
+    let mut parts = elf[begin..begin + len]
+        .split(|&b| b == 0)
+        .map(|part| String::from_utf8_lossy(part).into_owned());
+    let mut fields = Vec::new();
+    while let (Some(key), Some(value)) = (parts.next(), parts.next()) {
+        if key.is_empty() {
+            break;
+        }
+        fields.push((key, value));
+    }
+    fields
+}
+
+/// Read-only data of the ELF, where string literals live; the whole file if it does not parse
+fn rodata(elf: &[u8]) -> &[u8] {
+    let Ok(parsed) = Elf::parse(elf) else { return elf };
+    parsed.section_headers.iter()
+        .find(|sh| parsed.shdr_strtab.get_at(sh.sh_name) == Some(".rodata"))
+        .and_then(|sh| elf.get(sh.file_range()?))
+        .unwrap_or(elf)
+}
+
+/// Extracts security.txt, dependency versions and instruction names from a program ELF
+pub fn extract_metadata(elf: &[u8]) -> EmbeddedMetadata {
+    let rodata = rodata(elf);
+    let mut metadata = EmbeddedMetadata {
+        security_txt: security_txt(elf),
+        ..Default::default()
+    };
+
+    let crate_path = Regex::new(r"/([a-z][a-z0-9_-]*)-(\d+\.\d+\.\d+(?:-[a-z0-9.]+)?)/src/").unwrap();
+    for caps in crate_path.captures_iter(elf) {
+        let name = String::from_utf8_lossy(&caps[1]).into_owned();
+        let version = String::from_utf8_lossy(&caps[2]).into_owned();
+        metadata.crate_versions.entry(name).or_default().insert(version);
+    }
+
+    // String literals are stored back to back, so a name directly followed by the next
+    // `Instruction: ` literal has that literal's first word glued to it
+    let marker = b"Instruction: ";
+    let mut rest = rodata;
+    while let Some(start) = find(rest, marker) {
+        rest = &rest[start + marker.len()..];
+        let len = rest.iter().position(|b| !(b.is_ascii_alphanumeric() || *b == b'_')).unwrap_or(rest.len());
+        let mut name = &rest[..len];
+        if rest[len..].starts_with(b": ") {
+            name = name.strip_suffix(b"Instruction").unwrap_or(name);
+        }
+        let name = String::from_utf8_lossy(name).into_owned();
+        if !name.is_empty() && !ANCHOR_IDL_INSTRUCTIONS.contains(&name.as_str()) {
+            metadata.instruction_names.insert(name);
+        }
+    }
+
+    metadata
+}
+
+impl EmbeddedMetadata {
+    /// Versions of a crate, comma separated
+    fn versions(&self, name: &str) -> Option<String> {
+        self.crate_versions.get(name)
+            .map(|versions| versions.iter().cloned().collect::<Vec<_>>().join(", "))
+    }
+
+    /// Prints the metadata as shown under the intro banner
+    pub fn print(&self) {
+        if self.security_txt.is_empty() {
+            println!("security.txt:      not found");
+        } else {
+            println!("security.txt:");
+            for (key, value) in &self.security_txt {
+                println!("  {:<20} {}", key, value.replace('\n', "\n                       "));
+            }
+        }
+
+        for (label, name) in [("Anchor:", "anchor-lang"), ("solana-program:", "solana-program")] {
+            if let Some(versions) = self.versions(name) {
+                println!("{:<18} {}", label, versions);
+            }
+        }
+        let others: Vec<_> = self.crate_versions.keys()
+            .filter(|name| *name != "anchor-lang" && *name != "solana-program")
+            .map(|name| format!("{} {}", name, self.versions(name).unwrap_or_default()))
+            .collect();
+        if !others.is_empty() {
+            println!("Other crates:      {}", others.join(", "));
+        }
+
+        if !self.instruction_names.is_empty() {
+            println!("Instructions:      {}",
+                self.instruction_names.iter().cloned().collect::<Vec<_>>().join(", "));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_security_txt() {
+        let mut elf = b"junk".to_vec();
+        elf.extend_from_slice(SECURITY_TXT_BEGIN);
+        elf.extend_from_slice(b"name\0Example\0contacts\0email:sec@example.com\0");
+        elf.extend_from_slice(SECURITY_TXT_END);
+        assert_eq!(security_txt(&elf), [
+            ("name".to_string(), "Example".to_string()),
+            ("contacts".to_string(), "email:sec@example.com".to_string()),
+        ]);
+        assert!(security_txt(SECURITY_TXT_BEGIN).is_empty());
+    }
+
+    #[test]
+    fn extracts_crates_and_instructions() {
+        // Not an ELF, so the whole buffer stands in for .rodata
+        let data = b"/registry/src/index/anchor-lang-0.29.0/src/lib.rs\0\
+            Instruction: DepositInstruction: WithdrawInstruction: IdlWrite\0";
+        let metadata = extract_metadata(data);
+        assert_eq!(metadata.versions("anchor-lang").as_deref(), Some("0.29.0"));
+        let names: Vec<&str> = metadata.instruction_names.iter().map(String::as_str).collect();
+        assert_eq!(names, ["Deposit", "Withdraw"]);
+    }
+}
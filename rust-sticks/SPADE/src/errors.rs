@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::TransactionError;
+
+use crate::idl::{self, Idl};
+use crate::labels;
+use crate::logs::LogPatterns;
+use crate::txs::{self, ProgramTx};
+use crate::AccountFetcher;
+
+/// Example signatures kept per failure kind
+const MAX_EXAMPLES: usize = 3;
+
+/// Anchor framework error codes, used when neither the IDL nor the logs name an error
+const ANCHOR_ERRORS: &[(u32, &str)] = &[
+    (100, "InstructionMissing"),
+    (101, "InstructionFallbackNotFound"),
+    (102, "InstructionDidNotDeserialize"),
+    (103, "InstructionDidNotSerialize"),
+    (1000, "IdlInstructionStub"),
+    (2000, "ConstraintMut"),
+    (2001, "ConstraintHasOne"),
+    (2002, "ConstraintSigner"),
+    (2003, "ConstraintRaw"),
+    (2004, "ConstraintOwner"),
+    (2005, "ConstraintRentExempt"),
+    (2006, "ConstraintSeeds"),
+    (2007, "ConstraintExecutable"),
+    (2008, "ConstraintState"),
+    (2009, "ConstraintAssociated"),
+    (2010, "ConstraintAssociatedInit"),
+    (2011, "ConstraintClose"),
+    (2012, "ConstraintAddress"),
+    (2013, "ConstraintZero"),
+    (2014, "ConstraintTokenMint"),
+    (2015, "ConstraintTokenOwner"),
+    (2016, "ConstraintMintMintAuthority"),
+    (2017, "ConstraintMintFreezeAuthority"),
+    (2018, "ConstraintMintDecimals"),
+    (2019, "ConstraintSpace"),
+    (2020, "ConstraintAccountIsNone"),
+    (3000, "AccountDiscriminatorAlreadySet"),
+    (3001, "AccountDiscriminatorNotFound"),
+    (3002, "AccountDiscriminatorMismatch"),
+    (3003, "AccountDidNotDeserialize"),
+    (3004, "AccountDidNotSerialize"),
+    (3005, "AccountNotEnoughKeys"),
+    (3006, "AccountNotMutable"),
+    (3007, "AccountOwnedByWrongProgram"),
+    (3008, "InvalidProgramId"),
+    (3009, "InvalidProgramExecutable"),
+    (3010, "AccountNotSigner"),
+    (3011, "AccountNotSystemOwned"),
+    (3012, "AccountNotInitialized"),
+    (3013, "AccountNotProgramData"),
+    (3014, "AccountNotAssociatedTokenAccount"),
+    (3015, "AccountSysvarMismatch"),
+    (3016, "AccountReallocExceedsLimit"),
+    (3017, "AccountDuplicateReallocs"),
+    (4100, "DeclaredProgramIdMismatch"),
+    (5000, "Deprecated"),
+];
+
+/// Resolves an error code to (name, message) via the Anchor logs, or via the IDL and Anchor's
+/// built-ins when this program raised it (a CPI'd program's codes mean something else)
+fn resolve_error(
+    code: u32,
+    own_error: bool,
+    idl: Option<&Idl>,
+    anchor_log: Option<(&str, u32, &str)>,
+) -> (Option<String>, Option<String>) {
+    if let Some((name, msg)) = idl.filter(|_| own_error).and_then(|idl| idl.error(code)) {
+        return (Some(name.to_string()), msg.map(String::from));
+    }
+    if let Some((name, _, msg)) = anchor_log.filter(|(_, number, _)| *number == code) {
+        return (Some(name.to_string()), Some(msg.to_string()));
+    }
+    let builtin = ANCHOR_ERRORS.iter()
+        .find(|(c, _)| own_error && *c == code)
+        .map(|(_, name)| name.to_string());
+    (builtin, None)
+}
+
+/// Labels the instruction that failed: the program's own instruction if it was invoked
+/// directly or via CPI under that top-level instruction, otherwise the invoked program
+fn failed_instruction(tx: &ProgramTx, index: usize, program_id: &Pubkey, idl: Option<&Idl>, program_logs: &[&str]) -> String {
+    let anchor_name = program_logs.iter()
+        .find_map(|log| log.strip_prefix("Instruction: "))
+        .map(String::from);
+
+    match tx.invocations(program_id).find(|(i, _)| *i == index) {
+        Some((_, ix)) => {
+            let label = txs::instruction_label(idl, &ix.data);
+            match (idl.and_then(|idl| idl.instruction_name(&ix.data)), anchor_name) {
+                (None, Some(name)) => name,
+                _ => label,
+            }
+        }
+        None => match tx.instructions.get(index) {
+            Some(ix) => format!("other program: {}", labels::describe(&ix.program_id)),
+            None => "unknown".to_string(),
+        },
+    }
+}
+
+/// Aggregated failures of one kind
+#[derive(Default)]
+struct FailureStats {
+    name: Option<String>,
+    message: Option<String>,
+    raised_by: HashMap<String, usize>,
+    instructions: HashMap<String, usize>,
+    count: usize,
+    examples: Vec<String>,
+}
+
+/// Formats the top entries of a counter map
+fn top_counts(counts: &HashMap<String, usize>, limit: usize) -> String {
+    let mut entries: Vec<_> = counts.iter().collect();
+    entries.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    entries.iter()
+        .take(limit)
+        .map(|(name, count)| format!("{} x{}", name, count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Aggregates program logs and failures from recent transactions,
+/// ranking the most frequent errors with example signatures
+pub fn analyze_errors(fetcher: &mut AccountFetcher, args: &[String]) -> Result<()> {
+    let limit = txs::limit_from_args(args)?;
+    let idl = idl::from_args(args)?;
+    let program_id = fetcher.program_id;
+    let program_str = program_id.to_string();
+    let patterns = LogPatterns::get();
+
+    println!("\n🪵 PROGRAM LOG & ERROR ANALYSIS");
+    println!("==============================");
+
+    let txs = txs::fetch_transactions(fetcher, &program_id, limit)?;
+    if txs.is_empty() {
+        println!("\nNo transactions found");
+        return Ok(());
+    }
+
+    let mut failures: HashMap<String, FailureStats> = HashMap::new();
+    let mut log_counts: HashMap<String, (usize, String)> = HashMap::new();
+    let mut failed_txs = 0;
+
+    for tx in &txs {
+        let program_logs = patterns.program_logs(&tx.logs, &program_str);
+        for message in &program_logs {
+            let entry = log_counts.entry(patterns.normalize(message))
+                .or_insert_with(|| (0, message.to_string()));
+            entry.0 += 1;
+        }
+
+        let Some(error) = &tx.error else { continue };
+        failed_txs += 1;
+
+        let anchor_log = patterns.anchor_error(&tx.logs);
+        // Without a `failed` log line, the failing top-level instruction's program raised it
+        let failing = patterns.failing_program(&tx.logs)
+            .map(|(program, _)| program.parse::<Pubkey>().map_err(|_| program.to_string()))
+            .or_else(|| match error {
+                TransactionError::InstructionError(index, _) => {
+                    tx.instructions.get(*index as usize).map(|ix| Ok(ix.program_id))
+                }
+                _ => None,
+            });
+        let raised_by = match &failing {
+            Some(Ok(p)) if *p == program_id => "this program".to_string(),
+            Some(Ok(p)) => labels::describe(p),
+            Some(Err(program)) => program.clone(),
+            None => "unknown".to_string(),
+        };
+
+        let (key, name, message, instruction) = match error {
+            TransactionError::InstructionError(index, InstructionError::Custom(code)) => {
+                let own_error = matches!(failing, Some(Ok(p)) if p == program_id);
+                let (name, message) = resolve_error(*code, own_error, idl.as_ref(), anchor_log);
+                let instruction = failed_instruction(tx, *index as usize, &program_id, idl.as_ref(), &program_logs);
+                (format!("custom {} ({:#x}) from {}", code, code, raised_by), name, message, instruction)
+            }
+            TransactionError::InstructionError(index, err) => {
+                let instruction = failed_instruction(tx, *index as usize, &program_id, idl.as_ref(), &program_logs);
+                (err.to_string(), None, None, instruction)
+            }
+            err => (err.to_string(), None, None, "-".to_string()),
+        };
+
+        let stats = failures.entry(key).or_default();
+        stats.count += 1;
+        stats.name = stats.name.take().or(name);
+        stats.message = stats.message.take().or(message);
+        *stats.raised_by.entry(raised_by).or_default() += 1;
+        *stats.instructions.entry(instruction).or_default() += 1;
+        if stats.examples.len() < MAX_EXAMPLES {
+            stats.examples.push(tx.signature.clone());
+        }
+    }
+
+    println!("\nAnalyzed {} transactions, {} failed ({:.1}%)",
+        txs.len(), failed_txs, 100.0 * failed_txs as f64 / txs.len() as f64);
+
+    let mut ranked: Vec<_> = failures.iter().collect();
+    ranked.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.count));
+
+    if ranked.is_empty() {
+        println!("\n✅ No failed transactions");
+    } else {
+        println!("\n❌ Most frequent failures:");
+        for (rank, (key, stats)) in ranked.iter().enumerate() {
+            let name = stats.name.as_deref().map(|n| format!(" {}", n)).unwrap_or_default();
+            println!("\n#{} {}{} — {} failures ({:.1}% of failed)",
+                rank + 1, key, name, stats.count, 100.0 * stats.count as f64 / failed_txs as f64);
+            if let Some(message) = &stats.message {
+                println!("   Message:      {}", message);
+            }
+            println!("   Instructions: {}", top_counts(&stats.instructions, 5));
+            println!("   Raised by:    {}", top_counts(&stats.raised_by, 3));
+            println!("   Examples:");
+            for signature in &stats.examples {
+                println!("     {}", signature);
+            }
+        }
+    }
+
+    let mut logs: Vec<_> = log_counts.into_iter().collect();
+    logs.sort_by(|a, b| b.1.0.cmp(&a.1.0).then_with(|| a.0.cmp(&b.0)));
+
+    println!("\n📝 Most frequent program log messages:");
+    for (normalized, (count, example)) in logs.iter().take(25) {
+        println!("  {:>6}x  {}", count, normalized);
+        if example != normalized {
+            println!("           e.g. {}", example);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_builtins_only_for_own_errors() {
+        assert_eq!(resolve_error(2003, true, None, None).0.as_deref(), Some("ConstraintRaw"));
+        assert_eq!(resolve_error(2003, false, None, None).0, None);
+
+        let anchor_log = Some(("NotEnough", 6000, "Not enough funds"));
+        let (name, message) = resolve_error(6000, false, None, anchor_log);
+        assert_eq!((name.as_deref(), message.as_deref()), (Some("NotEnough"), Some("Not enough funds")));
+    }
+}
@@ -0,0 +1,109 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Result};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::pubkey::Pubkey;
+
+/// Parses a memcmp filter spec `OFFSET:VALUE`, where VALUE is `0x`-prefixed hex or a base58 pubkey
+pub fn parse_memcmp(spec: &str) -> Result<RpcFilterType> {
+    let (offset, value) = spec.split_once(':')
+        .ok_or_else(|| anyhow!("memcmp filter must look like OFFSET:VALUE, got '{}'", spec))?;
+    let offset = offset.parse::<usize>()
+        .map_err(|_| anyhow!("Invalid memcmp offset '{}'", offset))?;
+
+    let bytes = match value.strip_prefix("0x") {
+        Some(hex_str) => hex::decode(hex_str)?,
+        None => Pubkey::from_str(value)
+            .map_err(|_| anyhow!("memcmp value '{}' is neither 0x-hex nor a pubkey", value))?
+            .to_bytes()
+            .to_vec(),
+    };
+
+    Ok(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(offset, bytes)))
+}
+
+/// Builds a memcmp filter matching an 8-byte account discriminator at offset 0
+pub fn discriminator_filter(hex_str: &str) -> Result<RpcFilterType> {
+    let bytes = hex::decode(hex_str.trim_start_matches("0x"))?;
+    if bytes.len() != 8 {
+        bail!("Discriminator must be 8 bytes, got {}", bytes.len());
+    }
+    Ok(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, bytes)))
+}
+
+/// Collects getProgramAccounts filters from command line flags:
+/// `--discriminator HEX`, `--memcmp OFFSET:VALUE` (repeatable) and `--size BYTES`.
+/// Any other flag must be in `allowed` (flags taking a value are followed by their value)
+pub fn from_args(args: &[String], allowed: &[&str]) -> Result<Vec<RpcFilterType>> {
+    let mut filters = Vec::new();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().ok_or_else(|| anyhow!("{} needs a value", arg));
+        match arg.as_str() {
+            "--discriminator" => filters.push(discriminator_filter(value()?)?),
+            "--memcmp" => filters.push(parse_memcmp(value()?)?),
+            "--size" => {
+                let size = value()?;
+                filters.push(RpcFilterType::DataSize(size.parse()
+                    .map_err(|_| anyhow!("Invalid data size '{}'", size))?));
+            }
+            flag if allowed.contains(&flag) => {}
+            flag if flag.starts_with("--") => bail!("Unknown flag '{}'", flag),
+            _ => {}
+        }
+    }
+
+    Ok(filters)
+}
+
+/// Fails on any flag not in `allowed`, for commands that take no filters
+pub fn check_flags(args: &[String], allowed: &[&str]) -> Result<()> {
+    match args.iter().find(|arg| arg.starts_with("--") && !allowed.contains(&arg.as_str())) {
+        Some(flag) => bail!("Unknown flag '{}'", flag),
+        None => Ok(()),
+    }
+}
+
+/// Returns every value passed for a repeatable flag such as `--account`
+pub fn flag_values(args: &[String], flag: &str) -> Vec<String> {
+    args.windows(2)
+        .filter(|pair| pair[0] == flag)
+        .map(|pair| pair[1].clone())
+        .collect()
+}
+
+/// Applies filters locally, for notifications that were not filtered by the RPC node
+pub fn matches(filters: &[RpcFilterType], data: &[u8]) -> bool {
+    filters.iter().all(|filter| match filter {
+        RpcFilterType::DataSize(size) => data.len() as u64 == *size,
+        RpcFilterType::Memcmp(memcmp) => memcmp.bytes_match(data),
+        _ => true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_filters_and_allowed_flags() {
+        let filters = from_args(&args(&["--size", "165", "--account", "x", "--memcmp", "8:0x0102"]), &["--account"]).unwrap();
+        assert_eq!(filters.len(), 2);
+        assert!(matches(&filters, &[[0; 8].as_slice(), &[1, 2], &[0; 155]].concat()));
+        assert!(!matches(&filters, &[0; 165]));
+    }
+
+    #[test]
+    fn rejects_unknown_flags() {
+        assert!(from_args(&args(&["--sise", "165"]), &[]).is_err());
+        assert!(from_args(&args(&["--size"]), &[]).is_err());
+        assert!(discriminator_filter("0102").is_err());
+        assert!(check_flags(&args(&["--close", "--dryrun"]), &["--close", "--dry-run"]).is_err());
+        assert!(check_flags(&args(&["--close", "--keypair", "k"]), &["--close", "--keypair"]).is_ok());
+    }
+}
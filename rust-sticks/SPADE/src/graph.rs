@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::labels;
+
+/// A directed graph of pubkeys whose edges count occurrences per label,
+/// exported as Graphviz DOT or JSON
+pub struct Graph {
+    name: String,
+    /// Node id → display label
+    nodes: BTreeMap<String, String>,
+    /// (from, to) → edge label → count
+    edges: BTreeMap<(String, String), BTreeMap<String, usize>>,
+}
+
+/// Short display label: the known label, or the first and last characters of the address
+pub fn node_label(pubkey: &Pubkey) -> String {
+    match labels::label_for(pubkey) {
+        Some(label) => label.to_string(),
+        None => {
+            let address = pubkey.to_string();
+            format!("{}…{}", &address[..4], &address[address.len() - 4..])
+        }
+    }
+}
+
+impl Graph {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            nodes: BTreeMap::new(),
+            edges: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a node, keeping the first label given for it
+    pub fn add_node(&mut self, pubkey: &Pubkey, label: String) {
+        self.nodes.entry(pubkey.to_string()).or_insert(label);
+    }
+
+    /// Counts one occurrence of `from → to` under `label`, adding missing nodes
+    pub fn add_edge(&mut self, from: &Pubkey, to: &Pubkey, label: &str) {
+        self.add_node(from, node_label(from));
+        self.add_node(to, node_label(to));
+        *self.edges.entry((from.to_string(), to.to_string()))
+            .or_default()
+            .entry(label.to_string())
+            .or_default() += 1;
+    }
+
+    /// Renders the graph in Graphviz DOT format
+    pub fn to_dot(&self) -> String {
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut dot = format!("digraph \"{}\" {{\n  rankdir=LR;\n  node [shape=box];\n", escape(&self.name));
+        for (id, label) in &self.nodes {
+            dot.push_str(&format!("  \"{}\" [label=\"{}\"];\n", id, escape(label)));
+        }
+        for ((from, to), labels) in &self.edges {
+            let label = labels.iter()
+                .map(|(label, count)| format!("{} ×{}", escape(label), count))
+                .collect::<Vec<_>>()
+                .join("\\n");
+            dot.push_str(&format!("  \"{}\" -> \"{}\" [label=\"{}\"];\n", from, to, label));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Renders the graph as JSON: `{name, nodes: [{id, label}], edges: [{from, to, counts}]}`
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "name": self.name,
+            "nodes": self.nodes.iter()
+                .map(|(id, label)| json!({ "id": id, "label": label }))
+                .collect::<Vec<_>>(),
+            "edges": self.edges.iter()
+                .map(|((from, to), counts)| json!({ "from": from, "to": to, "counts": counts }))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    /// Writes the graph as JSON if `path` ends in `.json`, otherwise as DOT
+    pub fn write(&self, path: &str) -> Result<()> {
+        let is_json = Path::new(path).extension().is_some_and(|ext| ext == "json");
+        let contents = if is_json {
+            serde_json::to_string_pretty(&self.to_json())?
+        } else {
+            self.to_dot()
+        };
+        fs::write(path, contents)?;
+        println!("Graph with {} nodes and {} edges written to {}", self.nodes.len(), self.edges.len(), path);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports_dot_and_json() {
+        let (a, b) = (Pubkey::new_from_array([1; 32]), Pubkey::new_from_array([2; 32]));
+        let mut graph = Graph::new("CPI \"graph\"");
+        graph.add_node(&a, "vault".to_string());
+        graph.add_edge(&a, &b, "deposit");
+        graph.add_edge(&a, &b, "deposit");
+        graph.add_edge(&a, &b, "withdraw");
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph \"CPI \\\"graph\\\"\" {\n"));
+        assert!(dot.contains(&format!("  \"{}\" [label=\"vault\"];\n", a)));
+        assert!(dot.contains(&format!("  \"{}\" [label=\"{}\"];\n", b, node_label(&b))));
+        assert!(dot.contains(&format!("  \"{}\" -> \"{}\" [label=\"deposit ×2\\nwithdraw ×1\"];\n", a, b)));
+
+        let json = graph.to_json();
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 2);
+        assert_eq!(json["edges"], json!([{
+            "from": a.to_string(),
+            "to": b.to_string(),
+            "counts": { "deposit": 2, "withdraw": 1 },
+        }]));
+    }
+}
@@ -0,0 +1,143 @@
+use crate::layout::Field;
+
+/// Bytes shown per hexdump line
+const LINE_WIDTH: usize = 16;
+
+/// Minimum number of consecutive zero bytes reported as a zero run
+const MIN_ZERO_RUN: usize = 16;
+
+/// Finds runs of at least `min_len` zero bytes, returned as (start, len)
+pub fn zero_runs(data: &[u8], min_len: usize) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut start = None;
+
+    for (i, byte) in data.iter().enumerate() {
+        match (byte, start) {
+            (0, None) => start = Some(i),
+            (0, Some(_)) => {}
+            (_, Some(s)) => {
+                if i - s >= min_len {
+                    runs.push((s, i - s));
+                }
+                start = None;
+            }
+            (_, None) => {}
+        }
+    }
+    if let Some(s) = start {
+        if data.len() - s >= min_len {
+            runs.push((s, data.len() - s));
+        }
+    }
+
+    runs
+}
+
+/// Formats one line of hex and ASCII
+fn format_line(offset: usize, chunk: &[u8]) -> String {
+    let mut hex_part = String::with_capacity(LINE_WIDTH * 3 + 1);
+    for i in 0..LINE_WIDTH {
+        if i == LINE_WIDTH / 2 {
+            hex_part.push(' ');
+        }
+        match chunk.get(i) {
+            Some(b) => hex_part.push_str(&format!("{:02x} ", b)),
+            None => hex_part.push_str("   "),
+        }
+    }
+
+    let ascii: String = chunk.iter()
+        .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+        .collect();
+
+    format!("{:08x}  {} |{}|", offset, hex_part, ascii)
+}
+
+/// Annotation lines for the fields starting in `start..end`
+fn annotations(fields: &[Field], start: usize, end: usize) -> impl Iterator<Item = String> + '_ {
+    fields.iter()
+        .filter(move |f| f.offset >= start && f.offset < end)
+        .map(|field| format!("          ↳ +{:#06x} {} [{} bytes] = {}", field.offset, field.name, field.len, field.value))
+}
+
+/// Hexdump lines: offset, hex and ASCII columns, with field overlays under the line where
+/// each field starts and repeated all-zero lines collapsed
+fn hexdump_lines(data: &[u8], fields: &[Field]) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut collapsed_from: Option<usize> = None;
+
+    for (i, chunk) in data.chunks(LINE_WIDTH).enumerate() {
+        let offset = i * LINE_WIDTH;
+        let all_zero = chunk.len() == LINE_WIDTH && chunk.iter().all(|&b| b == 0);
+        let prev_zero = offset >= LINE_WIDTH
+            && data[offset - LINE_WIDTH..offset].iter().all(|&b| b == 0);
+        let is_last = offset + LINE_WIDTH >= data.len();
+
+        if all_zero && prev_zero && !is_last {
+            collapsed_from.get_or_insert(offset);
+            continue;
+        }
+
+        if let Some(from) = collapsed_from.take() {
+            lines.push(format!("*         ({} zero bytes)", offset - from));
+            lines.extend(annotations(fields, from, offset));
+        }
+
+        lines.push(format_line(offset, chunk));
+        lines.extend(annotations(fields, offset, offset + LINE_WIDTH));
+    }
+
+    lines
+}
+
+/// Prints an annotated hexdump followed by the zero runs
+pub fn print_hexdump(data: &[u8], fields: &[Field]) {
+    if data.is_empty() {
+        println!("(account has no data)");
+        return;
+    }
+
+    for line in hexdump_lines(data, fields) {
+        println!("{}", line);
+    }
+
+    let runs = zero_runs(data, MIN_ZERO_RUN);
+    if !runs.is_empty() {
+        let total: usize = runs.iter().map(|(_, len)| len).sum();
+        println!("\nZero runs (>= {} bytes), {} bytes total:", MIN_ZERO_RUN, total);
+        for (start, len) in runs {
+            println!("  {:#06x}..{:#06x} ({} bytes)", start, start + len, len);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_zero_runs() {
+        let mut data = vec![0u8; 20];
+        data.extend_from_slice(&[1, 0, 0, 1]);
+        data.extend_from_slice(&[0; 16]);
+        assert_eq!(zero_runs(&data, 16), [(0, 20), (24, 16)]);
+        assert_eq!(zero_runs(&data, 2), [(0, 20), (21, 2), (24, 16)]);
+        assert!(zero_runs(&[1, 2, 3], 1).is_empty());
+    }
+
+    #[test]
+    fn collapses_repeated_zero_lines() {
+        let mut data = vec![0xaa; 16];
+        data.extend_from_slice(&[0; 64]);
+        data.push(0xbb);
+        let fields = [Field::new(40, 8, "amount", "0")];
+
+        let lines = hexdump_lines(&data, &fields);
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].starts_with("00000000  aa aa"));
+        assert!(lines[1].starts_with("00000010  00 00"));
+        assert_eq!(lines[2], "*         (48 zero bytes)");
+        assert_eq!(lines[3], "          ↳ +0x0028 amount [8 bytes] = 0");
+        assert!(lines[4].starts_with("00000050  bb    "));
+    }
+}
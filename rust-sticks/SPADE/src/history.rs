@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+use chrono::DateTime;
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::labels;
+use crate::program::ProgramInfo;
+use crate::txs::{self, Ix, ProgramTx};
+use crate::AccountFetcher;
+
+/// An upgradeable loader instruction that changed the program
+struct UpgradeEvent {
+    signature: String,
+    slot: u64,
+    block_time: Option<i64>,
+    success: bool,
+    kind: &'static str,
+    /// The upgrade authority, or the payer for `ExtendProgram`
+    signer: Option<Pubkey>,
+    buffer: Option<Pubkey>,
+    new_authority: Option<Pubkey>,
+    /// Extra detail: rent recipient on close, added bytes on extend
+    detail: Option<String>,
+}
+
+/// Decodes an upgradeable loader instruction acting on `programdata`.
+/// Account positions follow `UpgradeableLoaderInstruction`
+fn decode_event(tx: &ProgramTx, ix: &Ix, programdata: &Pubkey) -> Option<UpgradeEvent> {
+    let account = |i: usize| ix.accounts.get(i).and_then(|&index| tx.account_keys.get(index).copied());
+    if !(0..ix.accounts.len()).any(|i| account(i) == Some(*programdata)) {
+        return None;
+    }
+    let tag = u32::from_le_bytes(ix.data.get(..4)?.try_into().ok()?);
+
+    let (kind, signer, buffer, new_authority, detail) = match tag {
+        2 => ("Deploy", account(7), account(3), None, None),
+        3 => ("Upgrade", account(6), account(2), None, None),
+        4 => ("SetAuthority", account(1), None, account(2), None),
+        5 => ("Close", account(2), None, None, account(1).map(|r| format!("rent to {}", r))),
+        6 => {
+            let bytes = u32::from_le_bytes(ix.data.get(4..8)?.try_into().ok()?);
+            ("ExtendProgram", account(3), None, None, Some(format!("+{} bytes", bytes)))
+        }
+        7 => ("SetAuthorityChecked", account(1), None, account(2), None),
+        _ => return None,
+    };
+
+    Some(UpgradeEvent {
+        signature: tx.signature.clone(),
+        slot: tx.slot,
+        block_time: tx.block_time,
+        success: tx.success(),
+        kind,
+        signer,
+        buffer,
+        new_authority,
+        detail,
+    })
+}
+
+fn format_time(block_time: Option<i64>) -> String {
+    block_time
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_else(|| "unknown time".to_string())
+}
+
+/// Reconstructs the deploy, upgrade, authority and close history of an upgradeable program
+/// from the transactions touching its ProgramData account
+pub fn analyze_upgrade_history(fetcher: &mut AccountFetcher, args: &[String]) -> Result<()> {
+    let limit = txs::limit_from_args(args)?;
+
+    println!("\n🕰️  PROGRAM UPGRADE HISTORY");
+    println!("==========================");
+
+    let info = ProgramInfo::fetch(fetcher)?;
+    let Some(programdata) = info.programdata else {
+        bail!("{} is not an upgradeable program, it has no upgrade history", fetcher.program_id);
+    };
+    println!("ProgramData: {}", programdata.address);
+
+    let loader = bpf_loader_upgradeable::id();
+    let txs = txs::fetch_transactions(fetcher, &programdata.address, limit)?;
+    // Signatures come newest first; the timeline reads oldest first
+    let events: Vec<UpgradeEvent> = txs.iter().rev()
+        .flat_map(|tx| tx.invocations(&loader).filter_map(|(_, ix)| decode_event(tx, ix, &programdata.address)))
+        .collect();
+    if events.is_empty() {
+        println!("\nNo loader instructions found in the last {} transactions", txs.len());
+        return Ok(());
+    }
+
+    println!("\n{:<20} {:>11}  {:<20} Details", "Time", "Slot", "Instruction");
+    let indent = " ".repeat(55);
+    for event in &events {
+        let status = if event.success { "" } else { " ❌ failed" };
+        println!("{:<20} {:>11}  {:<20} {}{}",
+            format_time(event.block_time), event.slot, event.kind, event.signature, status);
+        if let Some(signer) = event.signer {
+            println!("{}signer:  {}", indent, labels::describe(&signer));
+        }
+        if let Some(buffer) = event.buffer {
+            println!("{}buffer:  {}", indent, buffer);
+        }
+        if let Some(new_authority) = event.new_authority {
+            println!("{}new authority: {}", indent, labels::describe(&new_authority));
+        }
+        if matches!(event.kind, "SetAuthority" | "SetAuthorityChecked") && event.new_authority.is_none() {
+            println!("{}new authority: none (made immutable)", indent);
+        }
+        if let Some(detail) = &event.detail {
+            println!("{}{}", indent, detail);
+        }
+    }
+
+    let succeeded: Vec<_> = events.iter().filter(|e| e.success).collect();
+    let mut per_kind: BTreeMap<&str, usize> = BTreeMap::new();
+    for event in &succeeded {
+        *per_kind.entry(event.kind).or_default() += 1;
+    }
+    println!("\n📊 Summary ({} transactions scanned):", txs.len());
+    for (kind, count) in &per_kind {
+        println!("  {:<20} {:>6}", kind, count);
+    }
+    if let Some(last) = succeeded.iter().rev().find(|e| matches!(e.kind, "Deploy" | "Upgrade")) {
+        println!("Last code change: {} (slot {}) by {}",
+            format_time(last.block_time), last.slot,
+            last.signer.map(|s| labels::describe(&s)).unwrap_or_else(|| "unknown".to_string()));
+    }
+    if txs.len() >= limit {
+        println!("Note: only the last {} transactions were scanned, use --limit N to go further back", limit);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::txs::tests::{ix, program_tx};
+
+    /// Decodes a loader instruction whose account at position `i` is `keys[i]`
+    fn decode(tag: u32, len: usize, programdata_at: usize) -> (Vec<Pubkey>, UpgradeEvent) {
+        let keys: Vec<Pubkey> = (0..len).map(|_| Pubkey::new_unique()).collect();
+        let accounts: Vec<usize> = (0..len).collect();
+        let tx = program_tx(keys.clone(), vec![ix(bpf_loader_upgradeable::id(), &tag.to_le_bytes(), &accounts, 1)], Vec::new());
+        let event = decode_event(&tx, &tx.instructions[0], &keys[programdata_at]).unwrap();
+        (keys, event)
+    }
+
+    #[test]
+    fn reads_loader_account_positions() {
+        // Deploy: payer, programdata, program, buffer, rent, clock, system, authority
+        let (keys, deploy) = decode(2, 8, 1);
+        assert_eq!((deploy.kind, deploy.signer, deploy.buffer), ("Deploy", Some(keys[7]), Some(keys[3])));
+
+        // Upgrade: programdata, program, buffer, spill, rent, clock, authority
+        let (keys, upgrade) = decode(3, 7, 0);
+        assert_eq!((upgrade.kind, upgrade.signer, upgrade.buffer), ("Upgrade", Some(keys[6]), Some(keys[2])));
+
+        // SetAuthority: programdata, current authority, new authority
+        let (keys, set) = decode(4, 3, 0);
+        assert_eq!((set.kind, set.signer, set.new_authority), ("SetAuthority", Some(keys[1]), Some(keys[2])));
+
+        // Close: programdata, recipient, authority, program
+        let (keys, close) = decode(5, 4, 0);
+        assert_eq!((close.kind, close.signer), ("Close", Some(keys[2])));
+        assert_eq!(close.detail, Some(format!("rent to {}", keys[1])));
+    }
+
+    #[test]
+    fn tolerates_unknown_account_indices() {
+        let keys: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let loader = bpf_loader_upgradeable::id();
+        // Authority index past the keys, e.g. from an unloaded lookup table
+        let tx = program_tx(keys.clone(), vec![ix(loader, &3u32.to_le_bytes(), &[0, 1, 2, 9, 9, 9, 40], 1)], Vec::new());
+        let event = decode_event(&tx, &tx.instructions[0], &keys[0]).unwrap();
+        assert_eq!((event.signer, event.buffer), (None, Some(keys[2])));
+        assert!(decode_event(&tx, &tx.instructions[0], &Pubkey::new_unique()).is_none());
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::filters;
+use crate::labels;
+use crate::layout;
+use crate::token::{self, TokenKind};
+use crate::AccountFetcher;
+
+/// Holders listed by default
+const DEFAULT_TOP_HOLDERS: usize = 20;
+
+/// Offsets of the mint, owner and amount fields in a token account
+const MINT_OFFSET: usize = 0;
+const OWNER_OFFSET: usize = 32;
+const AMOUNT_OFFSET: usize = 64;
+
+/// getProgramAccounts filters for the token accounts of `mint`. Token accounts are exactly
+/// 165 bytes; Token-2022 accounts may carry extensions, so only the mint is matched there
+/// and mints are told apart when decoding
+fn holder_filters(mint: &Pubkey, program_id: &Pubkey) -> Vec<RpcFilterType> {
+    let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(MINT_OFFSET, mint.to_bytes().to_vec()))];
+    if *program_id == token::TOKEN_PROGRAM_ID {
+        filters.push(RpcFilterType::DataSize(token::ACCOUNT_LEN as u64));
+    }
+    filters
+}
+
+/// Gini coefficient of the balances: 0 when everyone holds the same, towards 1 when one
+/// holder has everything
+fn gini(balances: &[u64]) -> f64 {
+    let mut sorted: Vec<u64> = balances.to_vec();
+    sorted.sort_unstable();
+    let n = sorted.len() as f64;
+    let total: f64 = sorted.iter().map(|&b| b as f64).sum();
+    if sorted.is_empty() || total == 0.0 {
+        return 0.0;
+    }
+    let weighted: f64 = sorted.iter().enumerate().map(|(i, &b)| (i + 1) as f64 * b as f64).sum();
+    2.0 * weighted / (n * total) - (n + 1.0) / n
+}
+
+/// Reports the holder distribution of a mint: holder count, top holders, Gini coefficient
+/// and the share held by PDAs (off-curve owners, i.e. program-controlled accounts).
+/// Flags: `--top N`
+pub fn analyze_holders(mint: &str, args: &[String]) -> Result<()> {
+    let top = filters::flag_values(args, "--top")
+        .first()
+        .map(|n| n.parse::<usize>())
+        .transpose()?
+        .unwrap_or(DEFAULT_TOP_HOLDERS);
+
+    println!("\n👥 TOKEN HOLDER DISTRIBUTION");
+    println!("===========================");
+
+    let mint = Pubkey::from_str(mint)?;
+    let mut fetcher = AccountFetcher::new(&mint.to_string())?;
+    let mint_account = fetcher.with_retry(|client| Ok(client.get_account(&mint)?))?;
+    if !token::is_token_program(&mint_account.owner) {
+        bail!("{} is not a token mint (owner {})", mint, labels::describe(&mint_account.owner));
+    }
+    if token::decode(&mint_account.data).map(|t| t.kind) != Some(TokenKind::Mint) {
+        bail!("{} is a token program account but not a mint", mint);
+    }
+    let decimals = mint_account.data[44];
+    let supply = layout::read_u64(&mint_account.data, 36).unwrap_or(0);
+
+    println!("Mint:     {}", labels::describe(&mint));
+    println!("Program:  {}", labels::describe(&mint_account.owner));
+    println!("Supply:   {}", token::format_amount(supply, decimals));
+
+    let mut fetcher = AccountFetcher::new(&mint_account.owner.to_string())?;
+    let accounts = fetcher.get_filtered_accounts(holder_filters(&mint, &mint_account.owner))?;
+
+    let mut balances: HashMap<Pubkey, u64> = HashMap::new();
+    let mut token_accounts = 0;
+    let mut empty_accounts = 0;
+    for (_, account) in &accounts {
+        if token::decode(&account.data).map(|t| t.kind) != Some(TokenKind::Account) {
+            continue;
+        }
+        let (Some(owner), Some(amount)) = (
+            layout::read_pubkey(&account.data, OWNER_OFFSET),
+            layout::read_u64(&account.data, AMOUNT_OFFSET),
+        ) else { continue };
+        token_accounts += 1;
+        if amount == 0 {
+            empty_accounts += 1;
+            continue;
+        }
+        *balances.entry(owner).or_default() += amount;
+    }
+
+    let held: u64 = balances.values().sum();
+    let share = |amount: u64| 100.0 * amount as f64 / held.max(1) as f64;
+    println!("\nToken accounts: {} ({} empty)", token_accounts, empty_accounts);
+    println!("Holders:        {} owners with a non-zero balance", balances.len());
+    if balances.is_empty() {
+        return Ok(());
+    }
+    if held != supply {
+        println!("Held:           {} ({:.2}% of supply)",
+            token::format_amount(held, decimals), 100.0 * held as f64 / supply.max(1) as f64);
+    }
+
+    let mut ranked: Vec<(Pubkey, u64)> = balances.into_iter().collect();
+    ranked.sort_by_key(|(_, amount)| std::cmp::Reverse(*amount));
+
+    println!("\n🏆 Top {} holders:", top.min(ranked.len()));
+    println!("  {:>4}  {:<44} {:>24} {:>8}", "#", "Owner", "Balance", "Share");
+    for (rank, (owner, amount)) in ranked.iter().take(top).enumerate() {
+        let kind = if owner.is_on_curve() { "" } else { " [PDA]" };
+        println!("  {:>4}  {:<44} {:>24} {:>7.2}%{}",
+            rank + 1, owner.to_string(), token::format_amount(*amount, decimals), share(*amount), kind);
+        if let Some(label) = labels::label_for(owner) {
+            println!("        ({})", label);
+        }
+    }
+
+    let amounts: Vec<u64> = ranked.iter().map(|(_, amount)| *amount).collect();
+    let top_share = |n: usize| share(amounts.iter().take(n).sum());
+    let pda_held: u64 = ranked.iter().filter(|(owner, _)| !owner.is_on_curve()).map(|(_, amount)| amount).sum();
+    let pda_holders = ranked.iter().filter(|(owner, _)| !owner.is_on_curve()).count();
+
+    println!("\n📊 Concentration:");
+    println!("  Top 10 holders:   {:.2}%", top_share(10));
+    println!("  Top 100 holders:  {:.2}%", top_share(100));
+    println!("  Gini coefficient: {:.4}", gini(&amounts));
+    println!("  Held by PDAs:     {:.2}% across {} program-owned holders", share(pda_held), pda_holders);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gini_coefficient() {
+        assert_eq!(gini(&[]), 0.0);
+        assert_eq!(gini(&[0, 0]), 0.0);
+        assert!(gini(&[5, 5, 5, 5]).abs() < 1e-12);
+        // One holder of four has everything: (n - 1) / n
+        assert!((gini(&[0, 0, 100, 0]) - 0.75).abs() < 1e-12);
+        assert!((gini(&[1, 2, 3, 4]) - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn filters_by_program() {
+        let mint = Pubkey::new_unique();
+        assert_eq!(holder_filters(&mint, &token::TOKEN_PROGRAM_ID).len(), 2);
+        assert_eq!(holder_filters(&mint, &token::TOKEN_2022_PROGRAM_ID).len(), 1);
+    }
+}
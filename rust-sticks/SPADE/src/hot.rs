@@ -0,0 +1,193 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::idl::{self, Idl};
+use crate::labels;
+use crate::txs::{self, ProgramTx};
+use crate::AccountFetcher;
+
+/// Accounts shown in each ranking
+const TOP_ACCOUNTS: usize = 20;
+
+/// Write-lock statistics for one account
+#[derive(Default)]
+struct WriteStats {
+    /// Transactions write-locking the account
+    writes: usize,
+    failed: usize,
+    /// Writes per slot
+    per_slot: HashMap<u64, usize>,
+    /// Invocations of this program passing the account as writable, per instruction type
+    instructions: HashMap<String, usize>,
+}
+
+impl WriteStats {
+    /// Slots in which more than one sampled transaction write-locked the account
+    fn contended_slots(&self) -> usize {
+        self.per_slot.values().filter(|&&n| n > 1).count()
+    }
+
+    fn max_per_slot(&self) -> usize {
+        self.per_slot.values().copied().max().unwrap_or(0)
+    }
+}
+
+/// Write locks of a transaction sample, split between the program's accounts and others
+#[derive(Default)]
+struct WriteLocks {
+    program: HashMap<Pubkey, WriteStats>,
+    other: HashMap<Pubkey, WriteStats>,
+    txs_per_slot: HashMap<u64, usize>,
+}
+
+/// Counts the accounts each transaction write-locked, except the fee payer and the program,
+/// and the instruction types of this program passing its accounts as writable
+fn count_write_locks(txs: &[ProgramTx], program_id: &Pubkey, program_accounts: &HashSet<Pubkey>, idl: Option<&Idl>) -> WriteLocks {
+    let mut locks = WriteLocks::default();
+
+    for tx in txs {
+        *locks.txs_per_slot.entry(tx.slot).or_default() += 1;
+        let fee_payer = tx.fee_payer().copied();
+
+        for key in tx.writable_keys() {
+            if Some(*key) == fee_payer || key == program_id {
+                continue;
+            }
+            let stats = if program_accounts.contains(key) {
+                locks.program.entry(*key).or_default()
+            } else {
+                locks.other.entry(*key).or_default()
+            };
+            stats.writes += 1;
+            if !tx.success() {
+                stats.failed += 1;
+            }
+            *stats.per_slot.entry(tx.slot).or_default() += 1;
+        }
+
+        for (_, ix) in tx.invocations(program_id) {
+            let label = txs::instruction_label(idl, &ix.data);
+            let written: HashSet<usize> = ix.accounts.iter()
+                .copied()
+                .filter(|&i| tx.writable.get(i).copied().unwrap_or(false))
+                .collect();
+            for key in written.into_iter().filter_map(|index| tx.account_keys.get(index)) {
+                if let Some(stats) = locks.program.get_mut(key) {
+                    *stats.instructions.entry(label.clone()).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    locks
+}
+
+/// Accounts by write count, most written first
+fn rank(writes: &HashMap<Pubkey, WriteStats>) -> Vec<(&Pubkey, &WriteStats)> {
+    let mut ranked: Vec<_> = writes.iter().collect();
+    ranked.sort_by_key(|(pubkey, stats)| (std::cmp::Reverse(stats.writes), **pubkey));
+    ranked
+}
+
+/// Ranks the accounts most often write-locked by the program's recent transactions,
+/// with per-slot contention estimates and the instruction types writing them
+pub fn analyze_hot_accounts(fetcher: &mut AccountFetcher, args: &[String]) -> Result<()> {
+    let limit = txs::limit_from_args(args)?;
+    let idl = idl::from_args(args)?;
+    let program_id = fetcher.program_id;
+
+    println!("\n🔥 HOT ACCOUNT & WRITE-LOCK CONTENTION ANALYSIS");
+    println!("==============================================");
+
+    let accounts: HashMap<Pubkey, Vec<u8>> = fetcher.get_all_accounts()?.into_iter().collect();
+    println!("Loaded {} program accounts", accounts.len());
+
+    let txs = txs::fetch_transactions(fetcher, &program_id, limit)?;
+    if txs.is_empty() {
+        println!("\nNo transactions found");
+        return Ok(());
+    }
+
+    let program_accounts: HashSet<Pubkey> = accounts.keys().copied().collect();
+    let WriteLocks { program: program_writes, other: other_writes, txs_per_slot } =
+        count_write_locks(&txs, &program_id, &program_accounts, idl.as_ref());
+
+    let busy_slots = txs_per_slot.values().filter(|&&n| n > 1).count();
+    println!("\nAnalyzed {} transactions across {} slots ({} slots with more than one)",
+        txs.len(), txs_per_slot.len(), busy_slots);
+    println!("Program accounts write-locked: {} of {}", program_writes.len(), accounts.len());
+    println!("Note: contention is estimated from the sampled transactions only, so it is a lower bound");
+
+    let ranked = rank(&program_writes);
+
+    println!("\n🔥 Hottest program accounts:");
+    for (pubkey, stats) in ranked.iter().take(TOP_ACCOUNTS) {
+        let account_type = idl.as_ref()
+            .and_then(|idl| idl.account_name(&accounts[pubkey]))
+            .map(|name| format!(" [{}]", name))
+            .unwrap_or_default();
+        println!("\n  {}{}", pubkey, account_type);
+        println!("    Write-locked in {} txs ({:.1}% of sample), {} failed ({:.1}%)",
+            stats.writes, 100.0 * stats.writes as f64 / txs.len() as f64,
+            stats.failed, 100.0 * stats.failed as f64 / stats.writes as f64);
+        println!("    Slots: {} active, {} contended, max {} writers in one slot, {:.2} writers/slot",
+            stats.per_slot.len(), stats.contended_slots(), stats.max_per_slot(),
+            stats.writes as f64 / stats.per_slot.len() as f64);
+
+        let mut instructions: Vec<_> = stats.instructions.iter().collect();
+        instructions.sort_by_key(|(_, count)| std::cmp::Reverse(**count));
+        for (label, count) in instructions.iter().take(5) {
+            println!("      {:<40} {:>6}", label, count);
+        }
+    }
+
+    let others = rank(&other_writes);
+
+    println!("\n🌐 Other frequently write-locked accounts (shared with other programs or users):");
+    for (pubkey, stats) in others.iter().take(10) {
+        println!("  {} — {} txs, {} contended slots, {} failed",
+            labels::describe(pubkey), stats.writes, stats.contended_slots(), stats.failed);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::txs::tests::{ix, program_tx};
+
+    #[test]
+    fn counts_write_locks_and_contention() {
+        let [payer, program, pool, vault, oracle] = [(); 5].map(|_| Pubkey::new_unique());
+        let keys = vec![payer, program, pool, vault, oracle];
+        let swap = |slot, accounts: &[usize]| {
+            let mut tx = program_tx(keys.clone(), vec![ix(program, &[1], accounts, 1)], Vec::new());
+            tx.slot = slot;
+            tx
+        };
+        let mut failed = swap(10, &[2]);
+        failed.error = Some(solana_sdk::transaction::TransactionError::AccountInUse);
+        // The vault is locked but not passed to the program by the failed swap, and read-only
+        // in the next one
+        let mut read_vault = swap(11, &[2, 3]);
+        read_vault.writable[3] = false;
+        let txs = [swap(10, &[2, 3]), failed, read_vault, swap(12, &[3])];
+
+        let locks = count_write_locks(&txs, &program, &HashSet::from([pool, vault]), None);
+        assert_eq!(locks.txs_per_slot[&10], 2);
+        assert!(!locks.program.contains_key(&payer) && !locks.program.contains_key(&program));
+        assert_eq!(locks.other[&oracle].writes, 4);
+
+        let ranked = rank(&locks.program);
+        assert_eq!(ranked.iter().map(|(key, _)| **key).collect::<Vec<_>>(), [pool, vault]);
+        let pool_stats = &locks.program[&pool];
+        assert_eq!((pool_stats.writes, pool_stats.failed, pool_stats.contended_slots(), pool_stats.max_per_slot()), (4, 1, 1, 2));
+        assert_eq!(pool_stats.instructions["tag 1"], 3);
+        let vault_stats = &locks.program[&vault];
+        assert_eq!((vault_stats.writes, vault_stats.contended_slots()), (3, 1));
+        assert_eq!(vault_stats.instructions["tag 1"], 2);
+    }
+}
@@ -0,0 +1,421 @@
+use std::collections::HashMap;
+use std::fs;
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use solana_sdk::hash::hashv;
+
+use crate::layout::{Field, Reader};
+
+/// Elements of a `vec` or `array` field recorded before the rest are summarized
+const MAX_ELEMENTS: usize = 64;
+
+/// The parts of an Anchor IDL SPADE uses to label and decode data: instruction, account and
+/// error names, and the type definitions account fields are read with
+#[derive(Debug, Clone, Default)]
+pub struct Idl {
+    pub name: String,
+    pub instructions: Vec<(String, [u8; 8])>,
+    pub accounts: Vec<(String, [u8; 8])>,
+    /// (code, name, message)
+    pub errors: Vec<(u32, String, Option<String>)>,
+    /// Struct and enum definitions by name, including account types
+    pub types: HashMap<String, Value>,
+}
+
+/// Converts an IDL `camelCase` instruction name to the `snake_case` Rust name Anchor hashes.
+/// A run of capitals is one word, as in heck: `initializeNFT` → `initialize_nft`
+fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_lower) {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+/// Anchor discriminator: first 8 bytes of sha256("<namespace>:<name>")
+pub fn anchor_discriminator(namespace: &str, name: &str) -> [u8; 8] {
+    let hash = hashv(&[namespace.as_bytes(), b":", name.as_bytes()]);
+    hash.to_bytes()[..8].try_into().unwrap()
+}
+
+/// Reads an explicit `discriminator` array (Anchor 0.30+ IDLs)
+fn explicit_discriminator(item: &Value) -> Option<[u8; 8]> {
+    let bytes: Vec<u8> = item.get("discriminator")?
+        .as_array()?
+        .iter()
+        .map(|b| b.as_u64().map(|b| b as u8))
+        .collect::<Option<_>>()?;
+    bytes.try_into().ok()
+}
+
+/// Returns the `name` of every entry in a top-level IDL array
+fn named_items<'a>(json: &'a Value, key: &str) -> impl Iterator<Item = (&'a str, &'a Value)> {
+    json.get(key)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|item| item.get("name").and_then(Value::as_str).map(|name| (name, item)))
+}
+
+impl Idl {
+    /// Parses an Anchor IDL JSON document (legacy or 0.30+ format)
+    pub fn parse(json: &Value) -> Result<Self> {
+        let name = json.get("name")
+            .or_else(|| json.pointer("/metadata/name"))
+            .and_then(Value::as_str)
+            .unwrap_or("unknown")
+            .to_string();
+
+        let instructions = named_items(json, "instructions")
+            .map(|(name, item)| {
+                let disc = explicit_discriminator(item)
+                    .unwrap_or_else(|| anchor_discriminator("global", &to_snake_case(name)));
+                (name.to_string(), disc)
+            })
+            .collect();
+
+        let accounts = named_items(json, "accounts")
+            .map(|(name, item)| {
+                let disc = explicit_discriminator(item)
+                    .unwrap_or_else(|| anchor_discriminator("account", name));
+                (name.to_string(), disc)
+            })
+            .collect();
+
+        let errors = named_items(json, "errors")
+            .filter_map(|(name, item)| {
+                let code = item.get("code")?.as_u64()? as u32;
+                let msg = item.get("msg").and_then(Value::as_str).map(String::from);
+                Some((code, name.to_string(), msg))
+            })
+            .collect();
+
+        // Legacy IDLs define account structs inline; 0.30+ IDLs list them under `types`
+        let types = named_items(json, "types")
+            .chain(named_items(json, "accounts"))
+            .filter_map(|(name, item)| Some((name.to_string(), item.get("type")?.clone())))
+            .collect();
+
+        Ok(Self { name, instructions, accounts, errors, types })
+    }
+
+    /// Loads an IDL from a JSON file
+    pub fn load(path: &str) -> Result<Self> {
+        let json: Value = serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| anyhow!("Failed to parse IDL {}: {}", path, e))?;
+        Self::parse(&json)
+    }
+
+    /// Names the instruction whose discriminator prefixes `data`
+    pub fn instruction_name(&self, data: &[u8]) -> Option<&str> {
+        self.instructions.iter()
+            .find(|(_, disc)| data.starts_with(disc))
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Names the account type whose discriminator prefixes `data`
+    pub fn account_name(&self, data: &[u8]) -> Option<&str> {
+        self.accounts.iter()
+            .find(|(_, disc)| data.starts_with(disc))
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Decodes an account whose discriminator is in the IDL, field by field. Decoding stops at
+    /// the first field that cannot be read (unsupported type or truncated data), keeping the
+    /// fields read so far
+    pub fn decode_account(&self, data: &[u8]) -> Option<(&str, Vec<Field>)> {
+        let (name, _) = self.accounts.iter().find(|(_, disc)| data.starts_with(disc))?;
+        let mut reader = Reader::new(data);
+        reader.take(8, "discriminator", name.clone())?;
+        if let Some(ty) = self.types.get(name) {
+            self.read_defined(&mut reader, ty, "");
+        }
+        Some((name.as_str(), reader.fields))
+    }
+
+    /// Reads the fields of a struct, or the variant and fields of an enum
+    fn read_defined(&self, reader: &mut Reader, def: &Value, path: &str) -> Option<()> {
+        match def.get("kind")?.as_str()? {
+            "struct" => self.read_fields(reader, def.get("fields"), path),
+            "enum" => {
+                let index = *reader.peek(1)?.first()?;
+                let variant = def.get("variants")?.get(index as usize)?;
+                let variant_name = variant.get("name")?.as_str()?;
+                reader.take(1, if path.is_empty() { "variant" } else { path }, variant_name.to_string())?;
+                self.read_fields(reader, variant.get("fields"), &join(path, variant_name))
+            }
+            _ => None,
+        }
+    }
+
+    /// Reads named (`{name, type}`) or tuple (bare type) fields in order
+    fn read_fields(&self, reader: &mut Reader, fields: Option<&Value>, path: &str) -> Option<()> {
+        for (i, field) in fields.and_then(Value::as_array).into_iter().flatten().enumerate() {
+            match field.get("name").and_then(Value::as_str) {
+                Some(name) => self.read_type(reader, field.get("type")?, &join(path, name))?,
+                None => self.read_type(reader, field, &join(path, &i.to_string()))?,
+            }
+        }
+        Some(())
+    }
+
+    /// Reads one Borsh value of an IDL type
+    fn read_type(&self, reader: &mut Reader, ty: &Value, path: &str) -> Option<()> {
+        if let Some(primitive) = ty.as_str() {
+            return read_primitive(reader, primitive, path);
+        }
+        if let Some(inner) = ty.get("option") {
+            return if reader.option(path)? { self.read_type(reader, inner, path) } else { Some(()) };
+        }
+        if let Some(inner) = ty.get("vec") {
+            let len = u32::from_le_bytes(reader.peek(4)?.try_into().ok()?) as usize;
+            reader.take(4, &format!("{}.len", path), len.to_string())?;
+            return self.read_elements(reader, inner, len, path);
+        }
+        if let Some(array) = ty.get("array") {
+            let inner = array.get(0)?;
+            let len = array.get(1)?.as_u64()? as usize;
+            if inner.as_str() == Some("u8") {
+                let bytes = reader.peek(len)?;
+                return reader.take(len, path, hex::encode(bytes)).map(|_| ());
+            }
+            return self.read_elements(reader, inner, len, path);
+        }
+        if let Some(defined) = ty.get("defined") {
+            // Legacy IDLs name the type directly, 0.30+ IDLs wrap it in `{ "name": ... }`
+            let name = defined.as_str().or_else(|| defined.get("name")?.as_str())?;
+            return self.read_defined(reader, self.types.get(name)?, path);
+        }
+        None
+    }
+
+    /// Reads `len` elements of a vec or array. Past `MAX_ELEMENTS` only fixed-size elements
+    /// can be skipped, as one summarized field
+    fn read_elements(&self, reader: &mut Reader, inner: &Value, len: usize, path: &str) -> Option<()> {
+        for i in 0..len.min(MAX_ELEMENTS) {
+            self.read_type(reader, inner, &format!("{}[{}]", path, i))?;
+        }
+        if len > MAX_ELEMENTS {
+            let size = inner.as_str().and_then(primitive_size)?;
+            let rest = len - MAX_ELEMENTS;
+            reader.take(rest.checked_mul(size)?, &format!("{}[{}..]", path, MAX_ELEMENTS), format!("{} more", rest))?;
+        }
+        Some(())
+    }
+
+    /// Looks up a custom error code
+    pub fn error(&self, code: u32) -> Option<(&str, Option<&str>)> {
+        self.errors.iter()
+            .find(|(c, _, _)| *c == code)
+            .map(|(_, name, msg)| (name.as_str(), msg.as_deref()))
+    }
+}
+
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() { name.to_string() } else { format!("{}.{}", path, name) }
+}
+
+/// Encoded size of a fixed-size primitive
+fn primitive_size(primitive: &str) -> Option<usize> {
+    match primitive {
+        "bool" | "u8" | "i8" => Some(1),
+        "u16" | "i16" => Some(2),
+        "u32" | "i32" | "f32" => Some(4),
+        "u64" | "i64" | "f64" => Some(8),
+        "u128" | "i128" => Some(16),
+        "publicKey" | "pubkey" => Some(32),
+        _ => None,
+    }
+}
+
+fn read_primitive(reader: &mut Reader, primitive: &str, path: &str) -> Option<()> {
+    match primitive {
+        "bool" => reader.bool(path).map(|_| ()),
+        "publicKey" | "pubkey" => reader.pubkey(path).map(|_| ()),
+        "string" => reader.string(path).map(|_| ()),
+        "bytes" => {
+            let len = u32::from_le_bytes(reader.peek(4)?.try_into().ok()?) as usize;
+            let bytes = reader.peek(4usize.checked_add(len)?)?;
+            reader.take(4 + len, path, hex::encode(&bytes[4..])).map(|_| ())
+        }
+        _ => {
+            let size = primitive_size(primitive)?;
+            let bytes = reader.peek(size)?;
+            let mut wide = [0u8; 16];
+            wide[..size].copy_from_slice(bytes);
+            let signed = primitive.starts_with('i');
+            if signed && bytes[size - 1] & 0x80 != 0 {
+                wide[size..].fill(0xff);
+            }
+            let value = match primitive {
+                "f32" => f32::from_le_bytes(bytes.try_into().ok()?).to_string(),
+                "f64" => f64::from_le_bytes(bytes.try_into().ok()?).to_string(),
+                _ if signed => i128::from_le_bytes(wide).to_string(),
+                _ => u128::from_le_bytes(wide).to_string(),
+            };
+            reader.take(size, path, value).map(|_| ())
+        }
+    }
+}
+
+/// Loads the IDL given with `--idl FILE`, or from SPADE_IDL, if any
+pub fn from_args(args: &[String]) -> Result<Option<Idl>> {
+    let path = crate::filters::flag_values(args, "--idl")
+        .into_iter()
+        .next()
+        .or_else(|| std::env::var("SPADE_IDL").ok());
+
+    match path {
+        Some(path) => {
+            let idl = Idl::load(&path)?;
+            println!("Loaded IDL '{}': {} instructions, {} accounts, {} errors",
+                idl.name, idl.instructions.len(), idl.accounts.len(), idl.errors.len());
+            Ok(Some(idl))
+        }
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn values(fields: &[Field]) -> Vec<(usize, &str, &str)> {
+        fields.iter().map(|f| (f.offset, f.name.as_str(), f.value.as_str())).collect()
+    }
+
+    /// Legacy IDL: account structs inline, `defined` names the type directly
+    fn vault_idl() -> Idl {
+        Idl::parse(&json!({
+            "name": "vault",
+            "accounts": [{
+                "name": "Vault",
+                "type": { "kind": "struct", "fields": [
+                    { "name": "authority", "type": "publicKey" },
+                    { "name": "amount", "type": "u64" },
+                    { "name": "delta", "type": "i32" },
+                    { "name": "label", "type": "string" },
+                    { "name": "bump", "type": { "option": "u8" } },
+                    { "name": "fee", "type": { "option": "u64" } },
+                    { "name": "history", "type": { "vec": "u16" } },
+                    { "name": "seed", "type": { "array": ["u8", 4] } },
+                    { "name": "status", "type": { "defined": "Status" } }
+                ]}
+            }],
+            "types": [{
+                "name": "Status",
+                "type": { "kind": "enum", "variants": [
+                    { "name": "Idle" },
+                    { "name": "Active", "fields": [{ "name": "since", "type": "i64" }] }
+                ]}
+            }]
+        })).unwrap()
+    }
+
+    #[test]
+    fn walks_borsh_fields() {
+        let idl = vault_idl();
+        let authority = Pubkey::new_unique();
+        let mut data = anchor_discriminator("account", "Vault").to_vec();
+        data.extend_from_slice(authority.as_ref());
+        data.extend_from_slice(&1_000u64.to_le_bytes());
+        data.extend_from_slice(&(-5i32).to_le_bytes());
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(b"abc");
+        data.extend_from_slice(&[1, 254]);
+        data.push(0);
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&[1, 0, 2, 0]);
+        data.extend_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+        data.push(1);
+        data.extend_from_slice(&(-7i64).to_le_bytes());
+
+        let (name, fields) = idl.decode_account(&data).unwrap();
+        assert_eq!(name, "Vault");
+        assert!(fields[1].value.starts_with(&authority.to_string()));
+        let mut expected = vec![(0, "discriminator", "Vault")];
+        expected.extend([
+            (40, "amount", "1000"),
+            (48, "delta", "-5"),
+            (52, "label", "\"abc\""),
+            (60, "bump", "254"),
+            (61, "fee", "none"),
+            (62, "history.len", "2"),
+            (66, "history[0]", "1"),
+            (68, "history[1]", "2"),
+            (70, "seed", "deadbeef"),
+            (74, "status", "Active"),
+            (75, "status.Active.since", "-7"),
+        ]);
+        let mut actual = values(&fields);
+        actual.remove(1);
+        assert_eq!(actual, expected);
+        assert_eq!(fields.last().unwrap().end(), data.len());
+    }
+
+    #[test]
+    fn keeps_fields_read_before_truncation() {
+        let idl = vault_idl();
+        let mut data = anchor_discriminator("account", "Vault").to_vec();
+        data.extend_from_slice(&[7; 32]);
+        data.extend_from_slice(&[1, 2, 3]);
+
+        let (_, fields) = idl.decode_account(&data).unwrap();
+        assert_eq!(fields.len(), 2);
+        assert!(idl.decode_account(&[0; 16]).is_none());
+    }
+
+    #[test]
+    fn reads_new_format_and_summarizes_long_vecs() {
+        let idl = Idl::parse(&json!({
+            "metadata": { "name": "counter" },
+            "instructions": [{ "name": "bumpCounter", "discriminator": [9, 9, 9, 9, 9, 9, 9, 9] }],
+            "accounts": [{ "name": "Counter", "discriminator": [1, 2, 3, 4, 5, 6, 7, 8] }],
+            "types": [
+                { "name": "Counter", "type": { "kind": "struct", "fields": [
+                    { "name": "inner", "type": { "defined": { "name": "Inner" } } },
+                    { "name": "samples", "type": { "vec": "u8" } }
+                ]}},
+                { "name": "Inner", "type": { "kind": "struct", "fields": ["bool", "u128"] } }
+            ]
+        })).unwrap();
+        assert_eq!(idl.name, "counter");
+        assert_eq!(idl.instruction_name(&[9; 12]), Some("bumpCounter"));
+
+        let mut data = vec![1, 2, 3, 4, 5, 6, 7, 8, 1];
+        data.extend_from_slice(&u128::MAX.to_le_bytes());
+        data.extend_from_slice(&70u32.to_le_bytes());
+        data.extend_from_slice(&[3; 70]);
+
+        let (_, fields) = idl.decode_account(&data).unwrap();
+        let last = &fields[fields.len() - 1];
+        assert_eq!(values(&fields[1..4]), [
+            (8, "inner.0", "true"),
+            (9, "inner.1", u128::MAX.to_string().as_str()),
+            (25, "samples.len", "70"),
+        ]);
+        assert_eq!(fields.len(), 4 + MAX_ELEMENTS + 1);
+        assert_eq!((last.name.as_str(), last.value.as_str(), last.len), ("samples[64..]", "6 more", 6));
+    }
+
+    #[test]
+    fn snake_cases_legacy_instruction_names() {
+        assert_eq!(to_snake_case("initializeVault"), "initialize_vault");
+        assert_eq!(to_snake_case("initializeNFT"), "initialize_nft");
+        assert_eq!(to_snake_case("mintNFTEdition"), "mint_nft_edition");
+        assert_eq!(to_snake_case("setV2Config"), "set_v2_config");
+        let idl = Idl::parse(&json!({ "instructions": [{ "name": "initializeVault" }] })).unwrap();
+        assert_eq!(idl.instructions[0].1, anchor_discriminator("global", "initialize_vault"));
+    }
+}
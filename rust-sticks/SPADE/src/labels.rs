@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use solana_sdk::pubkey::Pubkey;
+
+/// Well-known programs, sysvars and mints, used to label pubkeys in output
+const KNOWN_ADDRESSES: &[(&str, &str)] = &[
+    ("11111111111111111111111111111111", "System Program"),
+    ("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA", "Token Program"),
+    ("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb", "Token-2022 Program"),
+    ("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL", "Associated Token Account Program"),
+    ("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s", "Metaplex Token Metadata"),
+    ("CndyV3LdqHUfDLmE5naZjVN8rBZz4tqhdefbAnjHG3JR", "Candy Machine v3"),
+    ("Guard1JwRhJkVH6XZhzoYxeBVQe872VH6QggF4BWmS9g", "Candy Guard"),
+    ("auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg", "Token Auth Rules"),
+    ("BPFLoaderUpgradeab1e11111111111111111111111", "BPF Upgradeable Loader"),
+    ("BPFLoader2111111111111111111111111111111111", "BPF Loader 2"),
+    ("BPFLoader1111111111111111111111111111111111", "BPF Loader"),
+    ("ComputeBudget111111111111111111111111111111", "Compute Budget Program"),
+    ("Stake11111111111111111111111111111111111111", "Stake Program"),
+    ("Vote111111111111111111111111111111111111111", "Vote Program"),
+    ("Config1111111111111111111111111111111111111", "Config Program"),
+    ("AddressLookupTab1e1111111111111111111111111", "Address Lookup Table Program"),
+    ("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr", "Memo Program"),
+    ("Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo", "Memo Program v1"),
+    ("SysvarRent111111111111111111111111111111111", "Rent Sysvar"),
+    ("SysvarC1ock11111111111111111111111111111111", "Clock Sysvar"),
+    ("Sysvar1nstructions1111111111111111111111111", "Instructions Sysvar"),
+    ("SysvarRecentB1ockHashes11111111111111111111", "Recent Blockhashes Sysvar"),
+    ("So11111111111111111111111111111111111111112", "Wrapped SOL"),
+    ("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", "USDC"),
+    ("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB", "USDT"),
+    ("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc", "Orca Whirlpool"),
+    ("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4", "Jupiter Aggregator v6"),
+    ("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH", "Pyth Oracle"),
+];
+
+/// `KNOWN_ADDRESSES` parsed once, so lookups compare keys instead of encoding base58
+fn known_addresses() -> &'static HashMap<Pubkey, &'static str> {
+    static KNOWN: OnceLock<HashMap<Pubkey, &'static str>> = OnceLock::new();
+    KNOWN.get_or_init(|| {
+        KNOWN_ADDRESSES.iter()
+            .map(|(address, label)| (Pubkey::from_str(address).expect("valid known address"), *label))
+            .collect()
+    })
+}
+
+/// Returns a human readable label for well-known addresses
+pub fn label_for(pubkey: &Pubkey) -> Option<&'static str> {
+    known_addresses().get(pubkey).copied()
+}
+
+/// Formats a pubkey with its label, e.g. `Tokenkeg... (Token Program)`
+pub fn describe(pubkey: &Pubkey) -> String {
+    match label_for(pubkey) {
+        Some(label) => format!("{} ({})", pubkey, label),
+        None => pubkey.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_known_addresses() {
+        assert_eq!(known_addresses().len(), KNOWN_ADDRESSES.len());
+        let token_program = Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap();
+        assert_eq!(label_for(&token_program), Some("Token Program"));
+        assert_eq!(describe(&Pubkey::default()), "11111111111111111111111111111111 (System Program)");
+        assert_eq!(label_for(&Pubkey::new_unique()), None);
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::HashSet;
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::labels;
+use crate::strings::{self, StringKind};
+
+/// A named, decoded region of account data
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub offset: usize,
+    pub len: usize,
+    pub name: String,
+    pub value: String,
+}
+
+impl Field {
+    pub fn new(offset: usize, len: usize, name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self { offset, len, name: name.into(), value: value.into() }
+    }
+
+    pub fn end(&self) -> usize {
+        self.offset + self.len
+    }
+}
+
+/// Reads a little-endian u64 at `offset`, if in bounds
+pub fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    data.get(offset..offset + 8).map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+}
+
+/// Reads a pubkey at `offset`, if in bounds
+pub fn read_pubkey(data: &[u8], offset: usize) -> Option<Pubkey> {
+    data.get(offset..offset + 32).and_then(|b| Pubkey::try_from(b).ok())
+}
+
+/// Fields holding a non-zero pubkey: 32 bytes whose value shows the key they contain,
+/// labeled or not. Decoders and the inferred layout both format pubkeys this way
+pub fn pubkey_fields<'a>(data: &[u8], fields: &'a [Field]) -> Vec<(&'a Field, Pubkey)> {
+    fields.iter()
+        .filter(|field| field.len == 32)
+        .filter_map(|field| {
+            let pubkey = read_pubkey(data, field.offset)?;
+            let shown = pubkey != Pubkey::default() && field.value.starts_with(&pubkey.to_string());
+            shown.then_some((field, pubkey))
+        })
+        .collect()
+}
+
+/// Reads a Borsh string (u32 LE length + UTF-8) at `offset`, with trailing NUL padding
+/// trimmed, returning it with its encoded length
+pub fn read_string(data: &[u8], offset: usize) -> Option<(String, usize)> {
+    let len = u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?) as usize;
+    let bytes = data.get(offset + 4..(offset + 4).checked_add(len)?)?;
+    let text = String::from_utf8_lossy(bytes).trim_end_matches('\0').to_string();
+    Some((text, 4 + len))
+}
+
+/// Name of an enum variant by index
+fn name_of(names: &[&str], value: u8) -> String {
+    names.get(value as usize).map(|n| n.to_string()).unwrap_or_else(|| format!("unknown ({})", value))
+}
+
+/// Reads Borsh fields in order, recording each as an annotated `Field`
+pub struct Reader<'a> {
+    data: &'a [u8],
+    pub offset: usize,
+    pub fields: Vec<Field>,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0, fields: Vec::new() }
+    }
+
+    pub fn take(&mut self, len: usize, name: &str, value: String) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.offset..self.offset + len)?;
+        self.fields.push(Field::new(self.offset, len, name, value));
+        self.offset += len;
+        Some(bytes)
+    }
+
+    pub fn peek(&self, len: usize) -> Option<&'a [u8]> {
+        self.data.get(self.offset..self.offset + len)
+    }
+
+    pub fn u8(&mut self, name: &str) -> Option<u8> {
+        let value = *self.peek(1)?.first()?;
+        self.take(1, name, value.to_string()).map(|_| value)
+    }
+
+    pub fn named_u8(&mut self, name: &str, names: &[&str]) -> Option<u8> {
+        let value = *self.peek(1)?.first()?;
+        self.take(1, name, name_of(names, value)).map(|_| value)
+    }
+
+    pub fn bool(&mut self, name: &str) -> Option<bool> {
+        let value = *self.peek(1)?.first()? != 0;
+        self.take(1, name, value.to_string()).map(|_| value)
+    }
+
+    pub fn u16(&mut self, name: &str) -> Option<u16> {
+        let value = u16::from_le_bytes(self.peek(2)?.try_into().ok()?);
+        self.take(2, name, value.to_string()).map(|_| value)
+    }
+
+    pub fn u32(&mut self, name: &str) -> Option<u32> {
+        let value = u32::from_le_bytes(self.peek(4)?.try_into().ok()?);
+        self.take(4, name, value.to_string()).map(|_| value)
+    }
+
+    pub fn u64(&mut self, name: &str) -> Option<u64> {
+        let value = read_u64(self.data, self.offset)?;
+        self.take(8, name, value.to_string()).map(|_| value)
+    }
+
+    pub fn i64(&mut self, name: &str) -> Option<i64> {
+        let value = i64::from_le_bytes(self.peek(8)?.try_into().ok()?);
+        self.take(8, name, value.to_string()).map(|_| value)
+    }
+
+    pub fn pubkey(&mut self, name: &str) -> Option<Pubkey> {
+        let value = read_pubkey(self.data, self.offset)?;
+        self.take(32, name, labels::describe(&value)).map(|_| value)
+    }
+
+    pub fn string(&mut self, name: &str) -> Option<String> {
+        let (value, len) = read_string(self.data, self.offset)?;
+        self.take(len, name, format!("{:?}", value)).map(|_| value)
+    }
+
+    /// Reads a Borsh `Option` tag, recording `None` values as a field
+    pub fn option(&mut self, name: &str) -> Option<bool> {
+        let present = *self.peek(1)?.first()? != 0;
+        if present {
+            self.offset += 1;
+        } else {
+            self.take(1, name, "none".to_string())?;
+        }
+        Some(present)
+    }
+}
+
+/// Infers a rough layout for account data without an IDL: the Anchor discriminator, pubkeys
+/// that point at known accounts, Borsh strings, then every other non-zero aligned 8-byte word
+/// read as u64 (and i64 when negative)
+pub fn infer_layout(data: &[u8], program_id: &Pubkey, program_accounts: &HashSet<Pubkey>) -> Vec<Field> {
+    let mut fields = Vec::new();
+
+    if data.len() >= 8 {
+        fields.push(Field::new(0, 8, "discriminator", hex::encode(&data[..8])));
+    }
+
+    let mut offset = if data.len() >= 8 { 8 } else { 0 };
+    while offset + 32 <= data.len() {
+        let pubkey = read_pubkey(data, offset).unwrap();
+        // All-zero keys are unset fields, not references to the System Program
+        let value = if pubkey == Pubkey::default() {
+            None
+        } else if pubkey == *program_id {
+            Some(format!("{} (this program)", pubkey))
+        } else if program_accounts.contains(&pubkey) {
+            Some(format!("{} (program account)", pubkey))
+        } else {
+            labels::label_for(&pubkey).map(|_| labels::describe(&pubkey))
+        };
+
+        match value {
+            Some(value) => {
+                fields.push(Field::new(offset, 32, "pubkey", value));
+                offset += 32;
+            }
+            None => offset += 1,
+        }
+    }
+
+    for s in strings::extract_strings(data, 3) {
+        if s.kind != StringKind::Borsh || fields.iter().any(|f| s.offset < f.end() && f.offset < s.offset + s.len) {
+            continue;
+        }
+        fields.push(Field::new(s.offset, s.len, "string", format!("{:?}", s.text)));
+    }
+
+    for offset in (0..data.len().saturating_sub(7)).step_by(8) {
+        let value = read_u64(data, offset).unwrap();
+        if value == 0 || fields.iter().any(|f| offset < f.end() && f.offset < offset + 8) {
+            continue;
+        }
+        let value = match value as i64 {
+            signed if signed < 0 => format!("{} (i64 {})", value, signed),
+            _ => value.to_string(),
+        };
+        fields.push(Field::new(offset, 8, "u64", value));
+    }
+
+    fields.sort_by_key(|f| f.offset);
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn infers_words_around_known_fields() {
+        let program_id = Pubkey::new_unique();
+        let mut data = vec![0u8; 8];
+        data.extend_from_slice(&42u64.to_le_bytes());
+        data.extend_from_slice(program_id.as_ref());
+        data.extend_from_slice(&(-2i64).to_le_bytes());
+        data.extend_from_slice(&[0; 8]);
+        data.extend_from_slice(&5u32.to_le_bytes());
+        data.extend_from_slice(b"hello");
+        data.extend_from_slice(&[0; 3]);
+
+        let fields = infer_layout(&data, &program_id, &HashSet::new());
+        let summary: Vec<(usize, &str, &str)> = fields.iter().map(|f| (f.offset, f.name.as_str(), f.value.as_str())).collect();
+        let this = format!("{} (this program)", program_id);
+        assert_eq!(summary, [
+            (0, "discriminator", "0000000000000000"),
+            (8, "u64", "42"),
+            (16, "pubkey", this.as_str()),
+            (48, "u64", "18446744073709551614 (i64 -2)"),
+            (64, "string", "\"hello\""),
+        ]);
+    }
+
+    #[test]
+    fn reader_records_fields() {
+        let mut data = vec![1u8, 0];
+        data.extend_from_slice(&7u64.to_le_bytes());
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(b"abc");
+
+        let mut reader = Reader::new(&data);
+        assert_eq!(reader.named_u8("state", &["a", "b"]), Some(1));
+        assert_eq!(reader.option("authority"), Some(false));
+        assert_eq!(reader.u64("amount"), Some(7));
+        assert_eq!(reader.string("name").as_deref(), Some("abc"));
+        assert_eq!(reader.u8("past end"), None);
+
+        let values: Vec<&str> = reader.fields.iter().map(|f| f.value.as_str()).collect();
+        assert_eq!(values, ["b", "none", "7", "\"abc\""]);
+        assert_eq!(reader.offset, data.len());
+    }
+
+    #[test]
+    fn finds_pubkey_fields() {
+        let key = Pubkey::new_unique();
+        let mut data = key.to_bytes().to_vec();
+        data.extend_from_slice(&[0; 32]);
+        data.extend_from_slice(&[7; 32]);
+        let fields = [
+            Field::new(0, 32, "authority", format!("{} (Label)", key)),
+            Field::new(32, 32, "unset", Pubkey::default().to_string()),
+            Field::new(64, 32, "hash", hex::encode([7; 32])),
+        ];
+        let found: Vec<(&str, Pubkey)> = pubkey_fields(&data, &fields).iter().map(|(f, p)| (f.name.as_str(), *p)).collect();
+        assert_eq!(found, [("authority", key)]);
+    }
+}
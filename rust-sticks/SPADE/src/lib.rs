@@ -0,0 +1,1060 @@
+use solana_client::rpc_config::{RpcProgramAccountsConfig, RpcAccountInfoConfig};
+use solana_client::rpc_filter::RpcFilterType;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use anyhow::Result;
+use std::str::FromStr;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::io;
+use dotenv::dotenv;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use chrono::DateTime;
+use chrono::Utc;
+
+mod alerts;
+mod buffers;
+mod candy;
+mod compute;
+mod cpi;
+pub mod decoder;
+mod diff;
+mod disasm;
+mod elf;
+mod errors;
+mod filters;
+mod graph;
+mod hexdump;
+mod history;
+mod holders;
+mod hot;
+pub mod idl;
+pub mod labels;
+pub mod layout;
+mod logs;
+mod metaplex;
+mod native;
+mod program;
+mod script;
+mod search;
+mod snapshot;
+mod sql;
+mod strings;
+mod token;
+mod txs;
+mod watch;
+
+/// Provides descriptions for each analysis type in SPADE
+fn get_analysis_description(choice: &str) -> &'static str {
+    match choice {
+        "1" => "Basic account analysis provides an overview of all accounts in the program, \
+                including total count, size distribution, and general statistics.",
+        "2" => "Large account analysis focuses on accounts exceeding a specified size, \
+                helping identify data-heavy accounts and their structure.",
+        "3" => "Specific size analysis allows you to examine accounts of an exact size, \
+                useful for finding accounts with similar structures.",
+        "4" => "Account type analysis groups accounts by their discriminator, \
+                helping identify different account types used in the program.",
+        "5" => "Pattern search finds several patterns at once across all accounts: hex with \
+                wildcards, pubkeys, strings, integers and ranges, optionally at a fixed offset.",
+        "6" => "Specific account analysis provides detailed information about a single account, \
+                including creation time, references, and data structure.",
+        "7" => "Zero bytes analysis examines the distribution of zero bytes in accounts, \
+                helping identify unused space and patterns.",
+        "8" => "Cross-reference analysis maps relationships between accounts, \
+                showing how accounts are connected within the program.",
+        "9" => "Account age analysis shows the timeline of account creation, \
+                helping understand the program's growth and usage patterns.",
+        "10" => "Strings analysis extracts printable text runs and Borsh strings from all accounts, \
+                 such as names, URIs and symbols, and reports their offsets and frequency.",
+        "11" => "Save snapshot writes all program accounts to a .spade file, \
+                 which can later be compared with `spade diff`.",
+        "12" => "Upgrade history lists every deploy, upgrade, authority change and close \
+                 of the program, with slot, time, signer and buffer.",
+        _ => "Invalid choice"
+    }
+}
+
+/// Prints command line usage
+fn print_usage() {
+    println!("Usage:");
+    println!("  spade <PROGRAM_ID> [--idl IDL.json] [--decoders DIR]");
+    println!("                                              interactive analysis");
+    println!("  spade snapshot <PROGRAM_ID> <FILE.spade>    save all program accounts");
+    println!("  spade diff <BEFORE.spade> <AFTER.spade> [--idl IDL.json] [--decoders DIR]");
+    println!("                                              compare two snapshots");
+    println!("  spade watch <PROGRAM_ID> [FILTERS] [--account PUBKEY]...");
+    println!("                                              stream account changes over WebSocket");
+    println!("  spade txs <PROGRAM_ID> [--limit N] [--idl IDL.json]");
+    println!("                                              instruction mix, signers and volume");
+    println!("  spade errors <PROGRAM_ID> [--limit N] [--idl IDL.json]");
+    println!("                                              rank failures and program log messages");
+    println!("  spade compute <PROGRAM_ID> [--limit N] [--idl IDL.json]");
+    println!("                                              compute units and priority fees per instruction");
+    println!("  spade cpi <PROGRAM_ID> [--limit N] [--idl IDL.json] [--export FILE.dot|FILE.json]");
+    println!("                                              programs invoked by and invoking this program");
+    println!("  spade hot <PROGRAM_ID> [--limit N] [--idl IDL.json]");
+    println!("                                              most write-locked accounts and slot contention");
+    println!("  spade buffers <AUTHORITY> [--close --keypair FILE] [--recipient PUBKEY] [--dry-run]");
+    println!("                                              find and close upgradeable loader buffers");
+    println!("  spade collection --update-authority PUBKEY | --creator PUBKEY [--collection MINT] [--items]");
+    println!("                                              Metaplex collection membership, creators, royalties");
+    println!("  spade holders <MINT> [--top N]              holder count, top holders, Gini and PDA share");
+    println!("  spade program info <PROGRAM_ID>             loader, authority, deploy slot and sizes");
+    println!("  spade program dump <PROGRAM_ID> [OUT.so]    extract the deployed ELF");
+    println!("  spade program verify <PROGRAM_ID> --so FILE compare the deployed ELF with a local build");
+    println!("  spade program disasm <PROGRAM_ID> [--so FILE] [--out FILE.asm]");
+    println!("                                              disassemble: functions, syscalls, strings");
+    println!("  spade program history <PROGRAM_ID> [--limit N]");
+    println!("                                              deploys, upgrades and authority changes");
+    println!("  spade alert <PROGRAM_ID> <RULES.toml> [FILTERS] [--poll SECONDS] [--idl IDL.json]");
+    println!("                                              evaluate alert rules on account changes");
+    println!("  spade script <FILE.rhai> [PROGRAM_ID | FILE.spade] [--idl IDL.json] [--decoders DIR]");
+    println!("                                              run a Rhai analysis over the accounts");
+    println!("  spade sql <PROGRAM_ID | FILE.spade | FILE.db> [\"QUERY\"] [--idl IDL.json] [--limit N] [--db FILE.db]");
+    println!("                                              query accounts, fields, refs and signatures in SQLite");
+    println!();
+    println!("--decoders DIR (or SPADE_DECODERS) decodes accounts with every IDL in DIR, wherever --idl is accepted.");
+    println!("Filters: --discriminator HEX, --memcmp OFFSET:VALUE (0x-hex or pubkey), --size BYTES");
+    println!("Set SPADE_RPC_URL / SPADE_WS_URL to use your own endpoints (e.g. http://127.0.0.1:8899).");
+}
+
+/// Displays the SPADE introduction banner with program information
+fn print_intro(fetcher: &mut AccountFetcher) {
+    println!("
+╔════════════════════════════════════════════════╗
+║                    S P A D E                   ║
+║        Solana Program Account Data Explorer    ║
+║                                                ║
+║ Program: {}  ║
+║                                                ║
+║                                                ║
+║  Version: 1.0.0                                ║
+╚════════════════════════════════════════════════╝
+", fetcher.program_id);
+
+    match program::ProgramInfo::fetch(fetcher) {
+        Ok(info) => info.print(),
+        Err(e) => println!("⚠️  Could not inspect program: {}", e),
+    }
+}
+
+/// Main account fetcher struct to handle RPC connections and program data
+struct AccountFetcher {
+    // List of RPC clients for redundancy and load balancing
+    clients: Vec<RpcClient>,
+    program_id: Pubkey,
+    current_client: usize,
+    // Client that served the last call, so the WebSocket URL matches the endpoint in use
+    last_client: usize,
+}
+
+impl AccountFetcher {
+    /// Creates a new AccountFetcher instance with multiple RPC endpoints
+    fn new(program_id: &str) -> Result<Self> {
+        // Initialize with multiple RPC endpoints for redundancy
+        let rpc_urls = vec![
+            "https://burned-young-snowflake.solana-mainnet.quiknode.pro/96e3f49289f987ccdd62dacc40990b20bd21f5ad/",
+            "https://skilled-sly-choice.solana-mainnet.quiknode.pro/5db92b766fd9b7ec4cc7e89101473c1d579aa98a/",
+            "https://aged-billowing-firefly.solana-mainnet.quiknode.pro/714c2bc2cba308a8c5fe4aee343d31b83b9f42d1/",
+            "https://distinguished-dry-sea.solana-mainnet.quiknode.pro/79528918b82740044a48a73406c3139caf8e729d/",
+            "https://solitary-yolo-ensemble.solana-mainnet.quiknode.pro/82fe22445068e050d80b27275910aa62734e2520/",
+            "https://summer-orbital-gas.solana-mainnet.quiknode.pro/dff876e9e6cb916bc741a761367a91f50ff5dd92/",
+            "https://serene-cosmopolitan-arrow.solana-mainnet.quiknode.pro/e5024a662e59587220837fbb749fe7cce477ca09/",
+            "https://neat-snowy-bird.solana-mainnet.quiknode.pro/14c0721161ba1af1c4ef91b0a568e2b24edeb9c5/",
+            "https://api.mainnet-beta.solana.com",
+            "https://solana-api.projectserum.com",
+            "https://rpc.ankr.com/solana",
+            "https://mainnet.rpcpool.com",
+        ];
+        // SPADE_RPC_URL (comma separated) overrides the defaults, e.g. for a local test validator
+        let rpc_urls: Vec<String> = match env::var("SPADE_RPC_URL") {
+            Ok(urls) => urls.split(',').map(|u| u.trim().to_string()).filter(|u| !u.is_empty()).collect(),
+            Err(_) => rpc_urls.into_iter().map(String::from).collect(),
+        };
+        let clients = rpc_urls.into_iter()
+            .map(RpcClient::new)
+            .collect();
+
+        let program_id = Pubkey::from_str(program_id)?;
+        
+        Ok(Self {
+            clients,
+            program_id,
+            current_client: 0,
+            last_client: 0,
+        })
+    }
+
+    /// Returns the next available RPC client in a round-robin fashion
+    fn get_client(&mut self) -> (&RpcClient, Pubkey) {
+        let client = &self.clients[self.current_client];
+        self.last_client = self.current_client;
+        self.current_client = (self.current_client + 1) % self.clients.len();
+        (client, self.program_id)
+    }
+
+    /// Fetches all accounts for the program, retrying with different RPC endpoints on failure
+    fn get_all_accounts(&mut self) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        Ok(self.get_all_accounts_with_meta()?
+            .into_iter()
+            .map(|(pubkey, account)| (pubkey, account.data))
+            .collect())
+    }
+
+    /// Fetches all accounts for the program including lamports and owner,
+    /// retrying with different RPC endpoints on failure
+    fn get_all_accounts_with_meta(&mut self) -> Result<Vec<(Pubkey, Account)>> {
+        self.get_filtered_accounts(Vec::new())
+    }
+
+    /// Fetches program accounts matching getProgramAccounts filters (memcmp, data size),
+    /// retrying with different RPC endpoints on failure
+    fn get_filtered_accounts(&mut self, filters: Vec<RpcFilterType>) -> Result<Vec<(Pubkey, Account)>> {
+        let mut last_error = None;
+        let config = RpcProgramAccountsConfig {
+            filters: if filters.is_empty() { None } else { Some(filters) },
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: None,
+                commitment: None,
+                min_context_slot: None,
+            },
+            with_context: None,
+        };
+        
+        // Try each client until one works
+        for _ in 0..self.clients.len() {
+            let (client, program_id) = self.get_client();
+            match client.get_program_accounts_with_config(&program_id, config.clone()) {
+                Ok(accounts) => {
+                    println!("Found {} accounts", accounts.len());
+                    return Ok(accounts);
+                }
+                Err(e) => {
+                    println!("RPC error, trying next endpoint: {:?}", e);
+                    last_error = Some(e);
+                    self.current_client = (self.current_client + 1) % self.clients.len();
+                }
+            }
+        }
+        
+        Err(anyhow::anyhow!("All RPC endpoints failed. Last error: {:?}", last_error))
+    }
+
+    /// Runs an RPC call, retrying with the next endpoint on failure
+    fn with_retry<T>(&mut self, call: impl Fn(&RpcClient) -> Result<T>) -> Result<T> {
+        let mut last_error = None;
+
+        for _ in 0..self.clients.len() {
+            let (client, _) = self.get_client();
+            match call(client) {
+                Ok(value) => return Ok(value),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        Err(anyhow::anyhow!("All RPC endpoints failed. Last error: {:?}", last_error))
+    }
+
+    /// Returns the WebSocket (pubsub) URL of the RPC endpoint that served the last call,
+    /// or SPADE_WS_URL when set
+    fn ws_url(&self) -> String {
+        if let Ok(url) = env::var("SPADE_WS_URL") {
+            return url;
+        }
+        let rpc_url = self.clients[self.last_client].url();
+        let ws_url = rpc_url.replacen("https://", "wss://", 1).replacen("http://", "ws://", 1);
+        // The test validator serves pubsub on the port after the RPC port
+        ws_url.replacen(":8899", ":8900", 1)
+    }
+}
+
+/// Performs basic analysis of all program accounts
+fn analyze_account_basic(fetcher: &mut AccountFetcher) -> Result<()> {
+    println!("\n📊 BASIC ACCOUNT ANALYSIS");
+    println!("=======================");
+    
+    let all_accounts = fetcher.get_all_accounts()?;
+    println!("Total accounts found: {}", all_accounts.len());
+    
+    let mut size_groups: HashMap<usize, Vec<(Pubkey, Vec<u8>)>> = HashMap::new();
+    for (pubkey, data) in &all_accounts {
+        size_groups.entry(data.len())
+            .or_default()
+            .push((*pubkey, data.clone()));
+    }
+    
+    println!("\nSize distribution:");
+    for (size, accounts) in &size_groups {
+        println!("- Size {} bytes: {} accounts", size, accounts.len());
+    }
+    
+    Ok(())
+}
+
+/// Analyzes accounts larger than a specified size
+fn analyze_large_accounts(fetcher: &mut AccountFetcher, show_offsets: bool, registry: &decoder::DecoderRegistry) -> Result<()> {
+    println!("\nEnter minimum size in bytes (default: 2000):");
+    let mut size_input = String::new();
+    io::stdin().read_line(&mut size_input)?;
+    
+    let min_size = size_input.trim().parse::<usize>().unwrap_or(2000);
+    
+    println!("\n🔍 LARGE ACCOUNT ANALYSIS (>{} bytes)", min_size);
+    println!("====================================");
+    
+    let all_accounts = fetcher.get_all_accounts()?;
+    
+    for (pubkey, data) in &all_accounts {
+        if data.len() > min_size {
+            println!("\nAccount: {}", pubkey);
+            println!("Size: {} bytes", data.len());
+            match registry.decode(data, &fetcher.program_id) {
+                Some(decoded) => decoded.print(),
+                None => println!("First 32 bytes: {:?}", &data[..data.len().min(32)]),
+            }
+            
+            if show_offsets {
+                println!("\nOffset analysis:");
+                for (i, chunk) in data.chunks(32).enumerate() {
+                    let key_str = bs58::encode(chunk).into_string();
+                    println!("Offset {}: {}", i * 32, key_str);
+                }
+            }
+        }
+    }
+    
+    Ok(())
+}
+
+/// Analyzes accounts of a specific size
+fn analyze_specific_size(fetcher: &mut AccountFetcher, registry: &decoder::DecoderRegistry) -> Result<()> {
+    println!("\nEnter exact size in bytes to analyze (e.g., 377):");
+    let mut size_input = String::new();
+    io::stdin().read_line(&mut size_input)?;
+    
+    let target_size = size_input.trim().parse::<usize>().unwrap_or(377);
+    
+    println!("\n🎯 SPECIFIC SIZE ANALYSIS ({} bytes)", target_size);
+    println!("====================================");
+    
+    let all_accounts = fetcher.get_all_accounts()?;
+    let specific_accounts: Vec<_> = all_accounts.iter()
+        .filter(|(_, data)| data.len() == target_size)
+        .collect();
+    
+    println!("Found {} accounts of size {} bytes", specific_accounts.len(), target_size);
+    
+    for (pubkey, data) in specific_accounts {
+        println!("\n📝 Account: {}", pubkey);
+        println!("Size: {} bytes", data.len());
+        if let Some(decoded) = registry.decode(data, &fetcher.program_id) {
+            decoded.print();
+            continue;
+        }
+        println!("First 32 bytes: {:?}", &data[..data.len().min(32)]);
+        
+        println!("\nOffset analysis:");
+        for (i, chunk) in data.chunks(32).enumerate() {
+            let key_str = bs58::encode(chunk).into_string();
+            println!("Offset {}: {}", i * 32, key_str);
+        }
+    }
+    
+    Ok(())
+}
+
+/// Groups and analyzes accounts by the type their decoder reports (SPL Token layouts and
+/// extensions, Token Metadata keys, native account states, IDL account names), falling back
+/// to the raw discriminator for accounts no decoder handles
+fn analyze_account_types(fetcher: &mut AccountFetcher, registry: &decoder::DecoderRegistry) -> Result<()> {
+    println!("\n🏷️  ACCOUNT TYPE ANALYSIS");
+    println!("=======================");
+    
+    let all_accounts = fetcher.get_all_accounts()?;
+    let mut type_groups: HashMap<String, Vec<(Pubkey, Vec<u8>)>> = HashMap::new();
+    
+    // Group accounts by their decoded type, or their discriminator
+    for (pubkey, data) in all_accounts {
+        let account_type = if let Some(decoded) = registry.decode(&data, &fetcher.program_id) {
+            decoded.type_name
+        } else if data.len() >= 8 {
+            format!("{:02x?}", &data[..8])
+        } else {
+            format!("Unknown ({} bytes)", data.len())
+        };
+        type_groups.entry(account_type)
+            .or_default()
+            .push((pubkey, data));
+    }
+    
+    println!("\nFound {} different account types", type_groups.len());
+    
+    // Analyze each type
+    for (account_type, accounts) in &type_groups {
+        println!("\n📌 Account Type: {}", account_type);
+        println!("Count: {} accounts", accounts.len());
+        
+        // Get unique sizes
+        let sizes: HashSet<_> = accounts.iter().map(|(_, data)| data.len()).collect();
+        println!("Sizes: {:?}", sizes);
+        
+        // Show example accounts
+        println!("\nExample accounts:");
+        for (pubkey, data) in accounts.iter().take(3) {
+            println!("- {} ({} bytes)", pubkey, data.len());
+            if let Some(decoded) = registry.decode(data, &fetcher.program_id) {
+                for field in decoded.fields.iter().take(8) {
+                    println!("  {:<40} {}", field.name, field.value);
+                }
+            } else if data.len() >= 32 {
+                println!("  First 32 bytes: {:02x?}", &data[..32]);
+            }
+        }
+        println!("---");
+    }
+    
+    Ok(())
+}
+
+/// Provides detailed analysis of a specific account including:
+/// - Creation time
+/// - Cross references
+/// - Data structure
+fn search_specific_account(fetcher: &mut AccountFetcher, registry: &decoder::DecoderRegistry) -> Result<()> {
+    println!("\n🔍 SPECIFIC ACCOUNT ANALYSIS");
+    println!("Enter account address:");
+    let mut addr_input = String::new();
+    io::stdin().read_line(&mut addr_input)?;
+    
+    let search_pubkey = Pubkey::from_str(addr_input.trim())?;
+    
+    // Get all data we need from the client first
+    let accounts = fetcher.get_all_accounts()?;
+    let (client, _) = fetcher.get_client();
+    
+    if let Some((_, data)) = accounts.iter().find(|(pubkey, _)| *pubkey == search_pubkey) {
+        println!("\nAccount found!");
+        println!("Size: {} bytes", data.len());
+        
+        // Get creation time
+        println!("\nFetching account history...");
+        let config = GetConfirmedSignaturesForAddress2Config {
+            before: None,
+            until: None,
+            limit: Some(1),
+            commitment: None,
+        };
+        
+        match client.get_signatures_for_address_with_config(&search_pubkey, config) {
+            Ok(signatures) => {
+                if let Some(oldest_sig) = signatures.last() {
+                    let block_time = oldest_sig.block_time.unwrap_or(0);
+                    let datetime = DateTime::<Utc>::from_timestamp(block_time, 0)
+                        .unwrap_or_default()
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string();
+                    
+                    println!("\n📅 Creation Time: {}", datetime);
+                    println!("🔗 First Transaction: {}", oldest_sig.signature);
+                }
+            }
+            Err(e) => {
+                println!("Failed to get account history: {}", e);
+            }
+        }
+        
+        // Cross-reference analysis
+        println!("\n🔄 Cross References Analysis:");
+        println!("==========================");
+        
+        let mut references_to = Vec::new();
+        let mut referenced_by = Vec::new();
+        
+        // Look for references in the target account's data
+        for (offset, window) in data.windows(32).enumerate() {
+            if let Ok(ref_pubkey) = Pubkey::try_from(window) {
+                // Check if this pubkey belongs to any program account
+                if accounts.iter().any(|(p, _)| p == &ref_pubkey) && ref_pubkey != search_pubkey {
+                    references_to.push((ref_pubkey, offset * 32));
+                }
+            }
+        }
+        
+        // Look for references to our account in other accounts
+        for (other_pubkey, other_data) in &accounts {
+            if other_pubkey != &search_pubkey {  // Skip self
+                for (offset, window) in other_data.windows(32).enumerate() {
+                    if let Ok(ref_pubkey) = Pubkey::try_from(window) {
+                        if ref_pubkey == search_pubkey {
+                            referenced_by.push((*other_pubkey, offset * 32));
+                        }
+                    }
+                }
+            }
+        }
+        
+        if !references_to.is_empty() {
+            println!("\n📤 This account references these program accounts:");
+            for (pubkey, offset) in references_to {
+                println!("   {} (at offset {})", pubkey, offset);
+            }
+        } else {
+            println!("\n📤 This account doesn't reference any other program accounts");
+        }
+        
+        if !referenced_by.is_empty() {
+            println!("\n📥 This account is referenced by these program accounts:");
+            for (pubkey, offset) in referenced_by {
+                println!("  ← {} (at offset {})", pubkey, offset);
+            }
+        } else {
+            println!("\n📥 This account is not referenced by any other program accounts");
+        }
+        
+        let program_accounts: HashSet<Pubkey> = accounts.iter().map(|(p, _)| *p).collect();
+        let decoded = registry.decode(data, &fetcher.program_id);
+        let fields = match &decoded {
+            Some(decoded) => decoded.fields.clone(),
+            None => layout::infer_layout(data, &fetcher.program_id, &program_accounts),
+        };
+
+        match token::find_token_references(fetcher, data, &fields, &program_accounts) {
+            Ok(token_refs) if !token_refs.is_empty() => {
+                println!("\n🪙 This account references these token accounts:");
+                for (offset, pubkey, token) in token_refs {
+                    println!("   {} (at offset {}): {}", pubkey, offset, token.summary());
+                }
+            }
+            Ok(_) => {}
+            Err(e) => println!("Failed to look up referenced token accounts: {}", e),
+        }
+        
+        if let Some(decoded) = &decoded {
+            decoded.print();
+        }
+        
+        println!("\n📝 Hexdump:");
+        println!("==========");
+        hexdump::print_hexdump(data, &fields);
+    } else {
+        println!("Account not found!");
+    }
+    
+    Ok(())
+}
+
+/// Analyzes distribution of zero bytes in accounts
+fn analyze_zero_patterns(fetcher: &mut AccountFetcher) -> Result<()> {
+    println!("\n0️⃣  ZERO BYTES ANALYSIS");
+    println!("=======================");
+    
+    let accounts = fetcher.get_all_accounts()?;
+    let mut results = Vec::new();
+    let mut percentage_groups: HashMap<u32, Vec<(Pubkey, usize, usize)>> = HashMap::new();
+    
+    // Analyze all accounts
+    for (pubkey, data) in accounts {
+        let zero_count = data.iter().filter(|&&b| b == 0).count();
+        let zero_ratio = zero_count as f64 / data.len() as f64;
+        let percentage = (zero_ratio * 100.0) as u32;
+        
+        percentage_groups.entry(percentage)
+            .or_default()
+            .push((pubkey, data.len(), zero_count));
+        
+        results.push((pubkey, data.len(), zero_count, percentage));
+    }
+    
+    // Show summary of percentages
+    println!("\nZero byte percentage distribution:");
+    println!("================================");
+    let mut percentages: Vec<_> = percentage_groups.keys().collect();
+    percentages.sort_unstable();
+    
+    for &percentage in &percentages {
+        let count = percentage_groups[percentage].len();
+        println!("{}%: {} accounts", percentage, count);
+    }
+    
+    // Ask for specific percentage
+    println!("\nEnter percentage to see details (or press Enter to see all):");
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    
+    let filtered_results = if let Ok(target_percentage) = input.trim().parse::<u32>() {
+        results.into_iter()
+            .filter(|(_, _, _, p)| *p == target_percentage)
+            .collect::<Vec<_>>()
+    } else {
+        results
+    };
+    
+    println!("\nDetailed analysis:");
+    println!("=================");
+    for (pubkey, size, zero_count, percentage) in filtered_results {
+        println!(
+            "Account: {}\n  Size: {} bytes\n  Zero bytes: {} ({}%)\n",
+            pubkey,
+            size,
+            zero_count,
+            percentage
+        );
+    }
+    
+    Ok(())
+}
+
+/// Maps relationships between accounts in the program
+fn analyze_cross_references(fetcher: &mut AccountFetcher) -> Result<()> {
+    println!("\n🔗 CROSS-REFERENCE ANALYSIS");
+    println!("=======================");
+    
+    let accounts = fetcher.get_all_accounts()?;
+    let mut references = HashMap::new();
+    
+    // Store all account pubkeys for quick lookup
+    let account_pubkeys: HashSet<_> = accounts.iter()
+        .map(|(pubkey, _)| pubkey)
+        .collect();
+    
+    println!("Analyzing {} accounts for cross-references...", accounts.len());
+    
+    // For each account's data
+    for (pubkey, data) in &accounts {
+        // Look through the data in 32-byte windows (size of a Pubkey)
+        for (offset, window) in data.windows(32).enumerate() {
+            // Try to convert the 32 bytes into a Pubkey
+            if let Ok(found_pubkey) = Pubkey::try_from(window) {
+                // Check if this pubkey belongs to one of our program's accounts
+                if account_pubkeys.contains(&found_pubkey) && found_pubkey != *pubkey {
+                    // Store the reference with its offset
+                    references.entry(*pubkey)
+                        .or_insert_with(Vec::new)
+                        .push((found_pubkey, offset));
+                }
+            }
+        }
+    }
+    
+    // Show results
+    if references.is_empty() {
+        println!("\nNo cross-references found between accounts!");
+        return Ok(());
+    }
+    
+    // Count references to each account
+    let mut reference_counts: HashMap<Pubkey, usize> = HashMap::new();
+    for refs in references.values() {
+        for (ref_pubkey, _) in refs {
+            *reference_counts.entry(*ref_pubkey).or_default() += 1;
+        }
+    }
+    
+    // Show summary
+    println!("\n📊 Reference Count Summary:");
+    let mut counts: Vec<_> = reference_counts.iter().collect();
+    counts.sort_by_key(|&(_, count)| std::cmp::Reverse(*count));
+    
+    println!("Found {} accounts with references", counts.len());
+    println!("\nHow many accounts to display? (press Enter for all):");
+    let mut limit_input = String::new();
+    io::stdin().read_line(&mut limit_input)?;
+    
+    let limit = if let Ok(num) = limit_input.trim().parse::<usize>() {
+        num
+    } else {
+        counts.len()
+    };
+
+    for (pubkey, count) in counts.iter().take(limit) {
+        println!("  {} is referenced {} times", pubkey, count);
+    }
+    
+    // Ask for detailed analysis
+    println!("\nEnter an account address to see its references (or press Enter to skip):");
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    
+    if let Ok(search_pubkey) = Pubkey::from_str(input.trim()) {
+        if let Some(refs) = references.get(&search_pubkey) {
+            println!("\n📍 Account {} references:", search_pubkey);
+            for (ref_pubkey, offset) in refs {
+                println!("  - {} (at offset {})", ref_pubkey, offset);
+            }
+        } else {
+            // Check if it's a referenced account
+            let referencing_accounts: Vec<_> = references.iter()
+                .filter(|(_, refs)| refs.iter().any(|(p, _)| p == &search_pubkey))
+                .collect();
+            
+            if !referencing_accounts.is_empty() {
+                println!("\n📍 Account {} is referenced by:", search_pubkey);
+                for (account, _) in referencing_accounts {
+                    println!("  - {}", account);
+                }
+            } else {
+                println!("No references found for this account!");
+            }
+        }
+    }
+
+    println!("\nEnter a file path to export the reference graph (.dot or .json, or press Enter to skip):");
+    let mut path = String::new();
+    io::stdin().read_line(&mut path)?;
+    let path = path.trim();
+    if !path.is_empty() {
+        let mut reference_graph = graph::Graph::new(&format!("Account references of {}", fetcher.program_id));
+        for (pubkey, refs) in &references {
+            for (ref_pubkey, offset) in refs {
+                reference_graph.add_edge(pubkey, ref_pubkey, &format!("+{}", offset));
+            }
+        }
+        reference_graph.write(path)?;
+    }
+    
+    Ok(())
+}
+
+/// Analyzes account creation timeline and groups by age
+fn analyze_account_ages(fetcher: &mut AccountFetcher) -> Result<()> {
+    println!("\n⏰ ACCOUNT AGE ANALYSIS");
+    println!("=====================");
+    
+    let (client, program_id) = fetcher.get_client();
+    
+    let accounts = client.get_program_accounts_with_config(
+        &program_id,
+        RpcProgramAccountsConfig {
+            filters: None,
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: None,
+                commitment: None,
+                min_context_slot: None,
+            },
+            with_context: Some(true),
+        }
+    )?;
+    
+    println!("Found {} accounts", accounts.len());
+    println!("\nFetching historical data for each account (this may take a while)...");
+    
+    let mut account_histories: Vec<(Pubkey, u64, String)> = Vec::new();
+    
+    // For each account, get its earliest transaction
+    for (pubkey, _) in accounts {
+        let config = GetConfirmedSignaturesForAddress2Config {
+            before: None,
+            until: None,
+            limit: Some(1),
+            commitment: None,
+        };
+        
+        match client.get_signatures_for_address_with_config(&pubkey, config) {
+            Ok(signatures) => {
+                if let Some(oldest_sig) = signatures.last() {
+                    let block_time = oldest_sig.block_time.unwrap_or(0);
+                    let datetime = DateTime::<Utc>::from_timestamp(block_time, 0)
+                        .unwrap_or_default()
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string();
+                    
+                    account_histories.push((pubkey, block_time as u64, datetime));
+                }
+            }
+            Err(e) => {
+                println!("Failed to get history for {}: {}", pubkey, e);
+            }
+        }
+        
+        // Add a small delay to avoid rate limiting
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    
+    // Sort by creation time
+    account_histories.sort_by_key(|(_pubkey, time, _datetime)| *time);
+    
+    // Group by month/year
+    let mut time_groups: HashMap<String, Vec<(Pubkey, u64, String)>> = HashMap::new();
+    
+    for (pubkey, time, datetime) in account_histories {
+        let month_year = DateTime::<Utc>::from_timestamp(time as i64, 0)
+            .unwrap_or_default()
+            .format("%Y-%m")
+            .to_string();
+            
+        time_groups.entry(month_year)
+            .or_default()
+            .push((pubkey, time, datetime));
+    }
+    
+    // Show summary
+    println!("\nAccount Creation Timeline:");
+    println!("========================");
+    
+    let mut months: Vec<_> = time_groups.keys().collect();
+    months.sort();
+    
+    for month in months {
+        let accounts = &time_groups[month];
+        println!("{}: {} accounts", month, accounts.len());
+    }
+    
+    // Ask for detailed view
+    println!("\nEnter month (YYYY-MM) to see details, or press Enter to skip:");
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    
+    let month = input.trim();
+    if !month.is_empty() {
+        if let Some(accounts) = time_groups.get(month) {
+            println!("\nDetailed view for {}:", month);
+            println!("=====================");
+            
+            for (pubkey, _time, datetime) in accounts {
+                println!("Account: {}\n  Created: {}\n", pubkey, datetime);
+            }
+        } else {
+            println!("No accounts found.");
+        }
+    }
+    
+    Ok(())
+}
+
+/// Runs the SPADE command line with the process arguments
+pub fn run() -> Result<()> {
+    run_with_decoders(|_| {})
+}
+
+/// Runs the SPADE command line with extra account decoders, tried after any loaded IDL and
+/// before the built-in ones in every command. A crate depending on SPADE calls this from its
+/// own `main` to decode in-house layouts without forking SPADE
+pub fn run_with_decoders(register: fn(&mut decoder::DecoderRegistry)) -> Result<()> {
+    decoder::set_custom_decoders(register);
+
+    // Load environment variables
+    dotenv().ok();
+    
+    // Validate command line arguments
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        println!("Please provide a program ID");
+        print_usage();
+        return Ok(());
+    }
+
+    match args[1].as_str() {
+        "snapshot" => {
+            if args.len() < 4 {
+                print_usage();
+                return Ok(());
+            }
+            let mut fetcher = AccountFetcher::new(&args[2])?;
+            return snapshot::save_snapshot(&mut fetcher, &args[3]);
+        }
+        "diff" => {
+            if args.len() < 4 {
+                print_usage();
+                return Ok(());
+            }
+            return diff::diff_snapshots(&args[2], &args[3], &args[4..]);
+        }
+        "watch" => {
+            if args.len() < 3 {
+                print_usage();
+                return Ok(());
+            }
+            let mut fetcher = AccountFetcher::new(&args[2])?;
+            return watch::watch_program(&mut fetcher, &args[3..]);
+        }
+        "txs" => {
+            if args.len() < 3 {
+                print_usage();
+                return Ok(());
+            }
+            let mut fetcher = AccountFetcher::new(&args[2])?;
+            return txs::analyze_transactions(&mut fetcher, &args[3..]);
+        }
+        "errors" => {
+            if args.len() < 3 {
+                print_usage();
+                return Ok(());
+            }
+            let mut fetcher = AccountFetcher::new(&args[2])?;
+            return errors::analyze_errors(&mut fetcher, &args[3..]);
+        }
+        "compute" => {
+            if args.len() < 3 {
+                print_usage();
+                return Ok(());
+            }
+            let mut fetcher = AccountFetcher::new(&args[2])?;
+            return compute::analyze_compute(&mut fetcher, &args[3..]);
+        }
+        "cpi" => {
+            if args.len() < 3 {
+                print_usage();
+                return Ok(());
+            }
+            let mut fetcher = AccountFetcher::new(&args[2])?;
+            return cpi::analyze_cpi_graph(&mut fetcher, &args[3..]);
+        }
+        "hot" => {
+            if args.len() < 3 {
+                print_usage();
+                return Ok(());
+            }
+            let mut fetcher = AccountFetcher::new(&args[2])?;
+            return hot::analyze_hot_accounts(&mut fetcher, &args[3..]);
+        }
+        "buffers" => {
+            if args.len() < 3 {
+                print_usage();
+                return Ok(());
+            }
+            return buffers::reclaim_buffers(&args[2], &args[3..]);
+        }
+        "collection" => return metaplex::analyze_collections(&args[2..]),
+        "holders" => {
+            if args.len() < 3 {
+                print_usage();
+                return Ok(());
+            }
+            return holders::analyze_holders(&args[2], &args[3..]);
+        }
+        "program" => {
+            if args.len() < 4 {
+                print_usage();
+                return Ok(());
+            }
+            let mut fetcher = AccountFetcher::new(&args[3])?;
+            return match args[2].as_str() {
+                "info" => {
+                    program::ProgramInfo::fetch(&mut fetcher)?.print();
+                    Ok(())
+                }
+                "dump" => program::dump_program(&mut fetcher, args.get(4).map(String::as_str)),
+                "disasm" => disasm::disassemble_program(&mut fetcher, &args[4..]),
+                "history" => history::analyze_upgrade_history(&mut fetcher, &args[4..]),
+                "verify" => match filters::flag_values(&args[4..], "--so").first() {
+                    Some(so_path) => program::verify_program(&mut fetcher, so_path),
+                    None => {
+                        print_usage();
+                        Ok(())
+                    }
+                },
+                _ => {
+                    print_usage();
+                    Ok(())
+                }
+            };
+        }
+        "alert" => {
+            if args.len() < 4 {
+                print_usage();
+                return Ok(());
+            }
+            let mut fetcher = AccountFetcher::new(&args[2])?;
+            return alerts::run_alerts(&mut fetcher, &args[3], &args[4..]);
+        }
+        "script" => {
+            if args.len() < 3 {
+                print_usage();
+                return Ok(());
+            }
+            return script::run_script(&args[2], &args[3..]);
+        }
+        "sql" => {
+            if args.len() < 3 {
+                print_usage();
+                return Ok(());
+            }
+            return sql::run_sql(&args[2], &args[3..]);
+        }
+        _ => {}
+    }
+    
+    let mut fetcher = AccountFetcher::new(&args[1])?;
+    let registry = decoder::DecoderRegistry::from_args(&args[2..])?;
+    
+    print_intro(&mut fetcher);
+    
+    loop {
+        println!("\n🔍 SPADE Analysis Options:");
+        println!("1. 📊 Basic account analysis");
+        println!("2. 📈 Large account analysis");
+        println!("3. 📏 Specific size analysis");
+        println!("4. 🏷️  Account type analysis");
+        println!("5. 🔎 Pattern search");
+        println!("6. 🎯 Specific account analysis");
+        println!("7. 0️⃣  Zero bytes analysis");
+        println!("8. 🔗 Cross-reference analysis");
+        println!("9. ⏰ Account age analysis");
+        println!("10. 🔤 Strings analysis");
+        println!("11. 💾 Save snapshot");
+        println!("12. 🕰️  Upgrade history");
+        println!("0. Exit");
+        println!("\nEnter choice (0-12):");
+        
+        let mut choice = String::new();
+        io::stdin().read_line(&mut choice)?;
+        let choice = choice.trim();
+
+        if choice == "0" {
+            println!("\nThank you for using SPADE!");
+            println!("Built by Bluewolf & IcarusxB");
+            break;
+        }
+        
+        println!("\n📝 Description:");
+        println!("=============");
+        println!("{}", get_analysis_description(choice));
+        
+        println!("\nPress Enter to proceed or 'n' to return:");
+        let mut proceed = String::new();
+        io::stdin().read_line(&mut proceed)?;
+        
+        if proceed.trim().to_lowercase() != "n" {
+            match choice {
+                "1" => analyze_account_basic(&mut fetcher)?,
+                "2" => {
+                    println!("Show detailed offsets? (y/n):");
+                    let mut show_offsets = String::new();
+                    io::stdin().read_line(&mut show_offsets)?;
+                    analyze_large_accounts(&mut fetcher, show_offsets.trim().to_lowercase() == "y", &registry)?
+                },
+                "3" => analyze_specific_size(&mut fetcher, &registry)?,
+                "4" => analyze_account_types(&mut fetcher, &registry)?,
+                "5" => search::search_pattern(&mut fetcher)?,
+                "6" => search_specific_account(&mut fetcher, &registry)?,
+                "7" => analyze_zero_patterns(&mut fetcher)?,
+                "8" => analyze_cross_references(&mut fetcher)?,
+                "9" => analyze_account_ages(&mut fetcher)?,
+                "10" => strings::analyze_strings(&mut fetcher)?,
+                "11" => {
+                    println!("Enter snapshot file path (default: snapshot.spade):");
+                    let mut path = String::new();
+                    io::stdin().read_line(&mut path)?;
+                    let path = if path.trim().is_empty() { "snapshot.spade" } else { path.trim() };
+                    snapshot::save_snapshot(&mut fetcher, path)?
+                },
+                "12" => history::analyze_upgrade_history(&mut fetcher, &[])?,
+                _ => println!("Invalid choice")
+            }
+        }
+        
+        println!("\n##########################Press Enter to continue...##########################");
+        let mut wait = String::new();
+        io::stdin().read_line(&mut wait)?;
+    }
+    
+    Ok(())
+}
+
+
+
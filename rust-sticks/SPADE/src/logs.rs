@@ -0,0 +1,146 @@
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+/// Compiled patterns for transaction log lines, shared by the log-based analyses
+pub struct LogPatterns {
+    invoke: Regex,
+    result: Regex,
+    anchor_error: Regex,
+    consumed: Regex,
+    pubkey: Regex,
+    number: Regex,
+}
+
+impl LogPatterns {
+    /// The patterns, compiled on first use
+    pub fn get() -> &'static Self {
+        static PATTERNS: OnceLock<LogPatterns> = OnceLock::new();
+        PATTERNS.get_or_init(Self::new)
+    }
+
+    fn new() -> Self {
+        Self {
+            invoke: Regex::new(r"^Program (\w+) invoke \[\d+\]$").unwrap(),
+            result: Regex::new(r"^Program (\w+) (success|failed: (.*))$").unwrap(),
+            consumed: Regex::new(r"^Program (\w+) consumed (\d+) of \d+ compute units$").unwrap(),
+            anchor_error: Regex::new(r"Error Code: (\w+)\. Error Number: (\d+)\. Error Message: (.*?)\.?$").unwrap(),
+            pubkey: Regex::new(r"\b[1-9A-HJ-NP-Za-km-z]{32,44}\b").unwrap(),
+            number: Regex::new(r"\b\d+\b").unwrap(),
+        }
+    }
+
+    /// `Program log:` messages emitted while `program_id` was executing (not its CPIs)
+    pub fn program_logs<'a>(&self, logs: &'a [String], program_id: &str) -> Vec<&'a str> {
+        let mut stack: Vec<&str> = Vec::new();
+        let mut messages = Vec::new();
+
+        for line in logs {
+            if let Some(caps) = self.invoke.captures(line) {
+                stack.push(caps.get(1).unwrap().as_str());
+            } else if self.result.is_match(line) {
+                stack.pop();
+            } else if let Some(message) = line.strip_prefix("Program log: ") {
+                if stack.last() == Some(&program_id) {
+                    messages.push(message);
+                }
+            }
+        }
+
+        messages
+    }
+
+    /// The first program to fail and its failure message
+    pub fn failing_program<'a>(&self, logs: &'a [String]) -> Option<(&'a str, &'a str)> {
+        logs.iter().find_map(|line| {
+            let caps = self.result.captures(line)?;
+            Some((caps.get(1)?.as_str(), caps.get(3)?.as_str()))
+        })
+    }
+
+    /// (name, number, message) from an Anchor `AnchorError ... Error Code:` log
+    pub fn anchor_error<'a>(&self, logs: &'a [String]) -> Option<(&'a str, u32, &'a str)> {
+        logs.iter().find_map(|line| {
+            let caps = self.anchor_error.captures(line)?;
+            Some((caps.get(1)?.as_str(), caps.get(2)?.as_str().parse().ok()?, caps.get(3)?.as_str()))
+        })
+    }
+
+    /// Compute units consumed by each invocation of `program_id`, in execution order,
+    /// from the `Program X consumed N of M compute units` log lines
+    pub fn compute_units(&self, logs: &[String], program_id: &str) -> Vec<Option<u64>> {
+        let mut units = Vec::new();
+        // (program, index into `units` for our program's frames)
+        let mut stack: Vec<(&str, Option<usize>)> = Vec::new();
+
+        for line in logs {
+            if let Some(caps) = self.invoke.captures(line) {
+                let program = caps.get(1).unwrap().as_str();
+                let slot = (program == program_id).then(|| {
+                    units.push(None);
+                    units.len() - 1
+                });
+                stack.push((program, slot));
+            } else if let Some(caps) = self.consumed.captures(line) {
+                if let Some((program, Some(slot))) = stack.last() {
+                    if *program == &caps[1] {
+                        units[*slot] = caps[2].parse().ok();
+                    }
+                }
+            } else if self.result.is_match(line) {
+                stack.pop();
+            }
+        }
+
+        units
+    }
+
+    /// Replaces pubkeys and numbers so similar messages group together
+    pub fn normalize(&self, message: &str) -> String {
+        let message = self.pubkey.replace_all(message, "<pubkey>");
+        self.number.replace_all(&message, "<n>").into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logs(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_nested_logs() {
+        let patterns = LogPatterns::get();
+        let logs = logs(&[
+            "Program AAA invoke [1]",
+            "Program log: Instruction: Deposit",
+            "Program BBB invoke [2]",
+            "Program log: inner",
+            "Program BBB failed: custom program error: 0x1",
+            "Program AAA failed: custom program error: 0x1",
+        ]);
+        assert_eq!(patterns.program_logs(&logs, "AAA"), ["Instruction: Deposit"]);
+        assert_eq!(patterns.failing_program(&logs), Some(("BBB", "custom program error: 0x1")));
+        assert_eq!(patterns.normalize("paid 500 to 11111111111111111111111111111111"), "paid <n> to <pubkey>");
+    }
+
+    #[test]
+    fn compute_units_per_invocation() {
+        let logs = logs(&[
+            "Program AAA invoke [1]",
+            "Program BBB invoke [2]",
+            "Program AAA invoke [3]",
+            "Program AAA consumed 300 of 1000 compute units",
+            "Program AAA success",
+            "Program BBB consumed 500 of 1200 compute units",
+            "Program BBB success",
+            "Program AAA consumed 2000 of 3000 compute units",
+            "Program AAA success",
+            "Program AAA invoke [1]",
+            "Program AAA failed: custom program error: 0x0",
+        ]);
+        assert_eq!(LogPatterns::get().compute_units(&logs, "AAA"), [Some(2000), Some(300), None]);
+    }
+}
@@ -0,0 +1,432 @@
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::filters;
+use crate::labels;
+use crate::layout::{Field, Reader};
+use crate::AccountFetcher;
+
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// `Key` of Metadata accounts
+const METADATA_KEY: u8 = 4;
+
+/// Offset of the first creator's address in a Metadata account. Name, symbol and URI are
+/// stored padded to 32, 10 and 200 bytes, so everything up to the creators is fixed
+const FIRST_CREATOR_OFFSET: usize = 1 + 32 + 32 + (4 + 32) + (4 + 10) + (4 + 200) + 2 + 1 + 4;
+
+/// Metaplex `Key` names, indexed by the account's first byte
+const KEY_NAMES: &[&str] = &[
+    "Uninitialized",
+    "EditionV1",
+    "MasterEditionV1",
+    "ReservationListV1",
+    "Metadata",
+    "ReservationListV2",
+    "MasterEditionV2",
+    "EditionMarker",
+    "UseAuthorityRecord",
+    "CollectionAuthorityRecord",
+    "TokenOwnedEscrow",
+    "TokenRecord",
+    "MetadataDelegate",
+    "EditionMarkerV2",
+    "HolderDelegate",
+];
+
+pub const TOKEN_STANDARDS: &[&str] = &[
+    "NonFungible",
+    "FungibleAsset",
+    "Fungible",
+    "NonFungibleEdition",
+    "ProgrammableNonFungible",
+    "ProgrammableNonFungibleEdition",
+];
+
+const TOKEN_DELEGATE_ROLES: &[&str] = &[
+    "Sale",
+    "Transfer",
+    "Utility",
+    "Staking",
+    "Standard",
+    "LockedTransfer",
+    "Migration",
+];
+
+const TOKEN_STATES: &[&str] = &["Unlocked", "Locked", "Listed"];
+
+#[derive(Debug, Clone)]
+pub struct Creator {
+    pub address: Pubkey,
+    pub verified: bool,
+    pub share: u8,
+}
+
+/// The parts of a Metadata account the collection census uses
+#[derive(Debug, Clone, Default)]
+pub struct Metadata {
+    pub update_authority: Pubkey,
+    pub mint: Pubkey,
+    pub name: String,
+    pub seller_fee_basis_points: u16,
+    pub creators: Vec<Creator>,
+    /// Collection key and whether membership is verified
+    pub collection: Option<(Pubkey, bool)>,
+    /// Set on collection NFTs: the number of verified items, when tracked
+    pub collection_size: Option<u64>,
+}
+
+/// Decodes a Metadata account
+fn read_metadata(reader: &mut Reader) -> Option<Metadata> {
+    let mut metadata = Metadata {
+        update_authority: reader.pubkey("update_authority")?,
+        mint: reader.pubkey("mint")?,
+        name: reader.string("name")?,
+        ..Default::default()
+    };
+    reader.string("symbol")?;
+    reader.string("uri")?;
+    metadata.seller_fee_basis_points = reader.u16("seller_fee_basis_points")?;
+
+    if reader.option("creators")? {
+        let count = u32::from_le_bytes(reader.peek(4)?.try_into().ok()?);
+        reader.take(4, "creators.len", count.to_string())?;
+        for i in 0..count {
+            let creator = Creator {
+                address: reader.pubkey(&format!("creators[{}].address", i))?,
+                verified: reader.bool(&format!("creators[{}].verified", i))?,
+                share: reader.u8(&format!("creators[{}].share", i))?,
+            };
+            metadata.creators.push(creator);
+        }
+    }
+    reader.bool("primary_sale_happened")?;
+    reader.bool("is_mutable")?;
+
+    read_optional_fields(reader, &mut metadata);
+    Some(metadata)
+}
+
+/// Reads the optional fields added by later program versions, stopping where the data ends
+fn read_optional_fields(reader: &mut Reader, metadata: &mut Metadata) -> Option<()> {
+    if reader.option("edition_nonce")? {
+        reader.u8("edition_nonce")?;
+    }
+    if reader.option("token_standard")? {
+        reader.named_u8("token_standard", TOKEN_STANDARDS)?;
+    }
+    if reader.option("collection")? {
+        let verified = reader.bool("collection.verified")?;
+        metadata.collection = Some((reader.pubkey("collection.key")?, verified));
+    }
+    if reader.option("uses")? {
+        reader.u8("uses.use_method")?;
+        reader.u64("uses.remaining")?;
+        reader.u64("uses.total")?;
+    }
+    if reader.option("collection_details")? {
+        // V1 { size } and V2 { padding } are both a tag followed by 8 bytes
+        if reader.u8("collection_details.version")? == 0 {
+            metadata.collection_size = Some(reader.u64("collection_details.size")?);
+        } else {
+            reader.take(8, "collection_details.padding", String::new())?;
+        }
+    }
+    if reader.option("programmable_config")? {
+        reader.u8("programmable_config.version")?;
+        if reader.option("programmable_config.rule_set")? {
+            reader.pubkey("programmable_config.rule_set")?;
+        }
+    }
+    Some(())
+}
+
+fn read_token_record(reader: &mut Reader) -> Option<()> {
+    reader.u8("bump")?;
+    reader.named_u8("state", TOKEN_STATES)?;
+    if reader.option("rule_set_revision")? {
+        reader.u64("rule_set_revision")?;
+    }
+    if reader.option("delegate")? {
+        reader.pubkey("delegate")?;
+    }
+    if reader.option("delegate_role")? {
+        reader.named_u8("delegate_role", TOKEN_DELEGATE_ROLES)?;
+    }
+    if reader.option("locked_transfer")? {
+        reader.pubkey("locked_transfer")?;
+    }
+    Some(())
+}
+
+/// A decoded Token Metadata program account
+#[derive(Debug, Clone)]
+pub struct MetaplexAccount {
+    pub kind: String,
+    pub fields: Vec<Field>,
+}
+
+/// Decodes Metadata, MasterEdition, Edition and TokenRecord accounts by their one-byte `Key`.
+/// Other account kinds are labelled but not decoded
+pub fn decode(data: &[u8]) -> Option<MetaplexAccount> {
+    let key = *data.first()?;
+    let kind = KEY_NAMES.get(key as usize)?.to_string();
+    let mut reader = Reader::new(data);
+    reader.take(1, "key", kind.clone())?;
+
+    match key {
+        4 => {
+            read_metadata(&mut reader);
+        }
+        1 => {
+            reader.pubkey("parent");
+            reader.u64("edition");
+        }
+        2 | 6 => {
+            reader.u64("supply");
+            if reader.option("max_supply") == Some(true) {
+                reader.u64("max_supply");
+            }
+            if key == 2 {
+                reader.pubkey("printing_mint");
+                reader.pubkey("one_time_printing_authorization_mint");
+            }
+        }
+        11 => {
+            read_token_record(&mut reader);
+        }
+        _ => {}
+    }
+
+    Some(MetaplexAccount { kind, fields: reader.fields })
+}
+
+/// Decodes a Metadata account into the fields the collection census uses
+pub fn decode_metadata(data: &[u8]) -> Option<Metadata> {
+    if data.first() != Some(&METADATA_KEY) {
+        return None;
+    }
+    let mut reader = Reader::new(data);
+    reader.offset = 1;
+    read_metadata(&mut reader)
+}
+
+/// The Metadata PDA of a mint
+pub fn metadata_address(mint: &Pubkey) -> Pubkey {
+    let program_id = TOKEN_METADATA_PROGRAM_ID;
+    Pubkey::find_program_address(&[b"metadata", program_id.as_ref(), mint.as_ref()], &program_id).0
+}
+
+/// Membership and royalty statistics for one collection key
+#[derive(Default)]
+struct CollectionStats {
+    items: Vec<Metadata>,
+    verified: usize,
+    update_authorities: BTreeMap<Pubkey, usize>,
+    royalties: BTreeMap<u16, usize>,
+    /// Creator → (items listing it, items where it is verified, shares seen)
+    creators: BTreeMap<Pubkey, (usize, usize, BTreeMap<u8, usize>)>,
+}
+
+impl CollectionStats {
+    fn add(&mut self, metadata: &Metadata) {
+        if metadata.collection.is_some_and(|(_, verified)| verified) {
+            self.verified += 1;
+        }
+        *self.update_authorities.entry(metadata.update_authority).or_default() += 1;
+        *self.royalties.entry(metadata.seller_fee_basis_points).or_default() += 1;
+        for creator in &metadata.creators {
+            let entry = self.creators.entry(creator.address).or_default();
+            entry.0 += 1;
+            if creator.verified {
+                entry.1 += 1;
+            }
+            *entry.2.entry(creator.share).or_default() += 1;
+        }
+        self.items.push(metadata.clone());
+    }
+}
+
+/// Formats `value (count)` pairs, most frequent first
+fn counted<K>(counts: &BTreeMap<K, usize>, format: impl Fn(&K) -> String) -> String {
+    let mut sorted: Vec<_> = counts.iter().collect();
+    sorted.sort_by_key(|(_, count)| std::cmp::Reverse(**count));
+    sorted.iter().map(|(k, count)| format!("{} ({})", format(k), count)).collect::<Vec<_>>().join(", ")
+}
+
+/// Groups the Metadata accounts with a given update authority or first creator by collection
+/// key, reporting verified/unverified membership, update authorities, creators and royalties.
+/// Flags: `--update-authority PUBKEY` or `--creator PUBKEY`, `--collection MINT`, `--items`
+pub fn analyze_collections(args: &[String]) -> Result<()> {
+    let update_authority = filters::flag_values(args, "--update-authority").first().map(|k| Pubkey::from_str(k)).transpose()?;
+    let creator = filters::flag_values(args, "--creator").first().map(|k| Pubkey::from_str(k)).transpose()?;
+    let only = filters::flag_values(args, "--collection").first().map(|k| Pubkey::from_str(k)).transpose()?;
+    let list_items = args.iter().any(|a| a == "--items");
+
+    let mut rpc_filters = vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, vec![METADATA_KEY]))];
+    match (update_authority, creator) {
+        (Some(authority), _) => rpc_filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(1, authority.to_bytes().to_vec()))),
+        (None, Some(creator)) => rpc_filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(FIRST_CREATOR_OFFSET, creator.to_bytes().to_vec()))),
+        (None, None) => bail!("--update-authority PUBKEY or --creator PUBKEY is required to select the metadata accounts"),
+    }
+
+    println!("\n🖼️  COLLECTION CENSUS");
+    println!("====================");
+
+    let mut fetcher = AccountFetcher::new(&TOKEN_METADATA_PROGRAM_ID.to_string())?;
+    let accounts = fetcher.get_filtered_accounts(rpc_filters)?;
+
+    let mut collections: HashMap<Option<Pubkey>, CollectionStats> = HashMap::new();
+    let mut undecodable = 0;
+    for (_, account) in &accounts {
+        let Some(metadata) = decode_metadata(&account.data) else {
+            undecodable += 1;
+            continue;
+        };
+        let key = metadata.collection.map(|(key, _)| key);
+        if only.is_some() && key != only {
+            continue;
+        }
+        collections.entry(key).or_default().add(&metadata);
+    }
+    if undecodable > 0 {
+        println!("⚠️  {} accounts could not be decoded", undecodable);
+    }
+
+    let mut sorted: Vec<_> = collections.iter().collect();
+    sorted.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.items.len()));
+
+    // Resolve collection NFT names and sizes from their own Metadata accounts
+    let keys: Vec<Pubkey> = sorted.iter().filter_map(|(key, _)| **key).collect();
+    let mut collection_metadata: HashMap<Pubkey, Metadata> = HashMap::new();
+    for batch in keys.chunks(100) {
+        let addresses: Vec<Pubkey> = batch.iter().map(metadata_address).collect();
+        let found = fetcher.with_retry(|client| Ok(client.get_multiple_accounts(&addresses)?))?;
+        for (key, account) in batch.iter().zip(found) {
+            if let Some(metadata) = account.and_then(|a| decode_metadata(&a.data)) {
+                collection_metadata.insert(*key, metadata);
+            }
+        }
+    }
+
+    for (key, stats) in sorted {
+        match key {
+            Some(key) => {
+                let name = collection_metadata.get(key).map(|m| format!(" \"{}\"", m.name)).unwrap_or_default();
+                println!("\n📚 Collection {}{}", key, name);
+                if let Some(size) = collection_metadata.get(key).and_then(|m| m.collection_size) {
+                    println!("  Size on collection NFT: {}", size);
+                }
+            }
+            None => println!("\n📚 No collection"),
+        }
+        println!("  Items: {} ({} verified, {} unverified)",
+            stats.items.len(), stats.verified, stats.items.len() - stats.verified);
+        println!("  Update authorities: {}", counted(&stats.update_authorities, labels::describe));
+        println!("  Royalties: {}", counted(&stats.royalties, |bps| format!("{:.2}%", *bps as f64 / 100.0)));
+        println!("  Creators:");
+        for (address, (listed, verified, shares)) in &stats.creators {
+            println!("    {} in {} items, verified in {}, share {}",
+                address, listed, verified, counted(shares, |share| format!("{}%", share)));
+        }
+
+        if list_items {
+            let mut items: Vec<_> = stats.items.iter().collect();
+            items.sort_by(|a, b| a.name.cmp(&b.name));
+            for item in items {
+                let status = match item.collection {
+                    Some((_, true)) => "✅ verified",
+                    Some((_, false)) => "❌ unverified",
+                    None => "",
+                };
+                println!("    {:<44} {:<32} {}", item.mint.to_string(), item.name, status);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn padded_string(data: &mut Vec<u8>, text: &str, padded_len: usize) {
+        data.extend_from_slice(&(padded_len as u32).to_le_bytes());
+        let mut bytes = text.as_bytes().to_vec();
+        bytes.resize(padded_len, 0);
+        data.extend_from_slice(&bytes);
+    }
+
+    /// A Metadata account with one verified creator, a verified collection and the optional
+    /// fields up to the collection
+    fn metadata(authority: Pubkey, mint: Pubkey, creator: Pubkey, collection: Pubkey) -> Vec<u8> {
+        let mut data = vec![METADATA_KEY];
+        data.extend_from_slice(authority.as_ref());
+        data.extend_from_slice(mint.as_ref());
+        padded_string(&mut data, "Spade #1", 32);
+        padded_string(&mut data, "SPD", 10);
+        padded_string(&mut data, "https://example.com/1.json", 200);
+        data.extend_from_slice(&500u16.to_le_bytes());
+        data.extend_from_slice(&[1, 1, 0, 0, 0]);
+        data.extend_from_slice(creator.as_ref());
+        data.extend_from_slice(&[1, 100]);
+        data.extend_from_slice(&[0, 1]); // primary_sale_happened, is_mutable
+        data.extend_from_slice(&[1, 254]); // edition_nonce
+        data.extend_from_slice(&[1, 4]); // token_standard
+        data.extend_from_slice(&[1, 1]); // collection, verified
+        data.extend_from_slice(collection.as_ref());
+        data.extend_from_slice(&[0, 0, 0]); // uses, collection_details, programmable_config
+        data
+    }
+
+    #[test]
+    fn decodes_metadata() {
+        let (authority, mint, creator, collection) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let data = metadata(authority, mint, creator, collection);
+        assert_eq!(&data[FIRST_CREATOR_OFFSET..FIRST_CREATOR_OFFSET + 32], creator.as_ref());
+
+        let decoded = decode_metadata(&data).unwrap();
+        assert_eq!((decoded.update_authority, decoded.mint), (authority, mint));
+        assert_eq!(decoded.name, "Spade #1");
+        assert_eq!(decoded.seller_fee_basis_points, 500);
+        assert_eq!(decoded.creators.len(), 1);
+        assert!(decoded.creators[0].verified && decoded.creators[0].share == 100);
+        assert_eq!(decoded.collection, Some((collection, true)));
+        assert_eq!(decoded.collection_size, None);
+
+        let account = decode(&data).unwrap();
+        assert_eq!(account.kind, "Metadata");
+        let field = |name: &str| account.fields.iter().find(|f| f.name == name).map(|f| f.value.as_str());
+        assert_eq!(field("token_standard"), Some("ProgrammableNonFungible"));
+        assert_eq!(field("uses"), Some("none"));
+        assert_eq!(account.fields.last().map(|f| f.end()), Some(data.len()));
+    }
+
+    #[test]
+    fn old_metadata_without_optional_fields() {
+        let mut data = metadata(Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        data.truncate(FIRST_CREATOR_OFFSET + 32 + 2 + 2);
+        let decoded = decode_metadata(&data).unwrap();
+        assert_eq!(decoded.collection, None);
+        assert_eq!(decoded.creators.len(), 1);
+    }
+
+    #[test]
+    fn decodes_master_edition() {
+        let mut data = vec![6];
+        data.extend_from_slice(&3u64.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(&10u64.to_le_bytes());
+        let account = decode(&data).unwrap();
+        assert_eq!(account.kind, "MasterEditionV2");
+        let values: Vec<&str> = account.fields.iter().map(|f| f.value.as_str()).collect();
+        assert_eq!(values, ["MasterEditionV2", "3", "10"]);
+        assert!(decode(&[200]).is_none());
+    }
+}
@@ -0,0 +1,361 @@
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{address_lookup_table, config, stake, system_program, vote};
+
+use crate::layout::{Field, Reader};
+
+/// Size of a nonce account
+const NONCE_LEN: usize = 80;
+
+/// Size of the lookup table metadata that precedes the addresses
+const LOOKUP_TABLE_META_LEN: usize = 56;
+
+/// Lookup table addresses shown in the account view
+const LOOKUP_TABLE_ADDRESSES_SHOWN: usize = 32;
+
+/// `prior_voters` is a circular buffer of 32 (pubkey, start epoch, end epoch) entries,
+/// followed by its index and `is_empty`
+const PRIOR_VOTERS_LEN: usize = 32 * (32 + 8 + 8) + 8 + 1;
+
+/// Deactivation slot of a lookup table that has not been deactivated
+const ACTIVE_SLOT: u64 = u64::MAX;
+
+/// A decoded native program account
+#[derive(Debug, Clone)]
+pub struct NativeAccount {
+    /// Type and state, e.g. `Stake (delegated)`
+    pub kind: String,
+    pub fields: Vec<Field>,
+    /// Summary lines printed after the fields
+    pub notes: Vec<String>,
+}
+
+/// Programs with a native decoder. System Program accounts are only decoded as nonces
+pub fn is_native_program(program_id: &Pubkey) -> bool {
+    [system_program::id(), stake::program::id(), vote::program::id(), address_lookup_table::program::id(), config::program::id()]
+        .contains(program_id)
+}
+
+fn u32_tag(reader: &mut Reader, name: &str, names: &[&str]) -> Option<u32> {
+    let tag = u32::from_le_bytes(reader.peek(4)?.try_into().ok()?);
+    let value = names.get(tag as usize).map(|n| n.to_string()).unwrap_or_else(|| format!("unknown ({})", tag));
+    reader.take(4, name, value).map(|_| tag)
+}
+
+fn decode_nonce(data: &[u8]) -> Option<NativeAccount> {
+    if data.len() != NONCE_LEN {
+        return None;
+    }
+    let mut reader = Reader::new(data);
+    u32_tag(&mut reader, "version", &["Legacy", "Current"])?;
+    let state = u32_tag(&mut reader, "state", &["Uninitialized", "Initialized"])?;
+    if state == 1 {
+        reader.pubkey("authority")?;
+        let hash = bs58::encode(reader.peek(32)?).into_string();
+        reader.take(32, "durable_nonce", hash)?;
+        reader.u64("lamports_per_signature")?;
+    }
+    let kind = if state == 1 { "Nonce" } else { "Nonce (uninitialized)" };
+    Some(NativeAccount { kind: kind.to_string(), fields: reader.fields, notes: Vec::new() })
+}
+
+fn epoch(reader: &mut Reader, name: &str) -> Option<u64> {
+    let value = u64::from_le_bytes(reader.peek(8)?.try_into().ok()?);
+    let formatted = if value == u64::MAX { "none".to_string() } else { value.to_string() };
+    reader.take(8, name, formatted).map(|_| value)
+}
+
+fn decode_stake(data: &[u8]) -> Option<NativeAccount> {
+    let mut reader = Reader::new(data);
+    let state = u32_tag(&mut reader, "state", &["Uninitialized", "Initialized", "Stake", "RewardsPool"])?;
+    let mut notes = Vec::new();
+    if state == 1 || state == 2 {
+        reader.u64("meta.rent_exempt_reserve")?;
+        reader.pubkey("meta.authorized.staker")?;
+        reader.pubkey("meta.authorized.withdrawer")?;
+        reader.i64("meta.lockup.unix_timestamp")?;
+        reader.u64("meta.lockup.epoch")?;
+        reader.pubkey("meta.lockup.custodian")?;
+    }
+    let kind = match state {
+        2 => {
+            let voter = reader.pubkey("stake.delegation.voter_pubkey")?;
+            let amount = reader.u64("stake.delegation.stake")?;
+            let activation = epoch(&mut reader, "stake.delegation.activation_epoch")?;
+            let deactivation = epoch(&mut reader, "stake.delegation.deactivation_epoch")?;
+            reader.take(8, "stake.delegation.warmup_cooldown_rate", "deprecated".to_string())?;
+            reader.u64("stake.credits_observed")?;
+            reader.u8("stake_flags");
+            notes.push(format!("Delegated {} lamports to {} from epoch {}", amount, voter, activation));
+            if deactivation == u64::MAX {
+                "Stake (delegated)"
+            } else {
+                notes.push(format!("Deactivated in epoch {}", deactivation));
+                "Stake (deactivated)"
+            }
+        }
+        1 => "Stake (initialized)",
+        3 => "Stake (rewards pool)",
+        _ => "Stake (uninitialized)",
+    };
+    Some(NativeAccount { kind: kind.to_string(), fields: reader.fields, notes })
+}
+
+/// Walks `VoteState` (1.14.11 and current layouts), whose votes, voters and credits are
+/// variable length
+fn read_vote_state(reader: &mut Reader, landed_votes: bool, notes: &mut Vec<String>) -> Option<()> {
+    reader.pubkey("node_pubkey")?;
+    reader.pubkey("authorized_withdrawer")?;
+    reader.u8("commission")?;
+
+    let votes = reader.peek(8).map(|b| u64::from_le_bytes(b.try_into().unwrap()))? as usize;
+    let vote_len = if landed_votes { 1 + 8 + 4 } else { 8 + 4 };
+    let last_vote = votes.checked_sub(1)
+        .and_then(|last| layout_u64(reader, 8 + last * vote_len + usize::from(landed_votes)));
+    let value = match last_vote {
+        Some(slot) => format!("{} votes, last slot {}", votes, slot),
+        None => "no votes".to_string(),
+    };
+    reader.take(8 + votes.checked_mul(vote_len)?, "votes", value)?;
+
+    if reader.option("root_slot")? {
+        reader.u64("root_slot")?;
+    }
+    let voters = reader.u64("authorized_voters.len")?;
+    for i in 0..voters {
+        reader.u64(&format!("authorized_voters[{}].epoch", i))?;
+        reader.pubkey(&format!("authorized_voters[{}].pubkey", i))?;
+    }
+    reader.take(PRIOR_VOTERS_LEN, "prior_voters", "circular buffer".to_string())?;
+
+    let credits = reader.peek(8).map(|b| u64::from_le_bytes(b.try_into().unwrap()))? as usize;
+    // Each entry is (epoch, credits, previous credits)
+    let latest = credits.checked_sub(1).and_then(|last| {
+        layout_u64(reader, 8 + last * 24).zip(layout_u64(reader, 8 + last * 24 + 8))
+    });
+    let value = match latest {
+        Some((epoch, total)) => format!("{} epochs, {} credits as of epoch {}", credits, total, epoch),
+        None => format!("{} epochs", credits),
+    };
+    reader.take(8 + credits.checked_mul(24)?, "epoch_credits", value)?;
+    if let Some((epoch, _)) = latest {
+        notes.push(format!("Voting through epoch {}", epoch));
+    }
+
+    reader.u64("last_timestamp.slot")?;
+    reader.i64("last_timestamp.timestamp")?;
+    Some(())
+}
+
+/// Reads a u64 `offset` bytes past the reader's position without consuming it
+fn layout_u64(reader: &Reader, offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(reader.peek(offset + 8)?[offset..].try_into().ok()?))
+}
+
+fn decode_vote(data: &[u8]) -> Option<NativeAccount> {
+    let mut reader = Reader::new(data);
+    let mut notes = Vec::new();
+    let version = u32_tag(&mut reader, "version", &["V0_23_5", "V1_14_11", "Current"])?;
+    match version {
+        0 => {
+            reader.pubkey("node_pubkey")?;
+            reader.pubkey("authorized_voter")?;
+            reader.u64("authorized_voter_epoch")?;
+        }
+        1 | 2 => {
+            read_vote_state(&mut reader, version == 2, &mut notes);
+        }
+        _ => return None,
+    }
+    Some(NativeAccount { kind: "Vote".to_string(), fields: reader.fields, notes })
+}
+
+fn decode_lookup_table(data: &[u8]) -> Option<NativeAccount> {
+    let mut reader = Reader::new(data);
+    let state = u32_tag(&mut reader, "state", &["Uninitialized", "LookupTable"])?;
+    if state != 1 {
+        return Some(NativeAccount { kind: "Lookup Table (uninitialized)".to_string(), fields: reader.fields, notes: Vec::new() });
+    }
+    let deactivation_slot = epoch(&mut reader, "deactivation_slot")?;
+    reader.u64("last_extended_slot")?;
+    reader.u8("last_extended_slot_start_index")?;
+    let frozen = !reader.option("authority")?;
+    if !frozen {
+        reader.pubkey("authority")?;
+    }
+
+    let addresses = data.len().saturating_sub(LOOKUP_TABLE_META_LEN) / 32;
+    reader.offset = LOOKUP_TABLE_META_LEN;
+    for i in 0..addresses.min(LOOKUP_TABLE_ADDRESSES_SHOWN) {
+        reader.pubkey(&format!("addresses[{}]", i))?;
+    }
+
+    let mut notes = vec![format!("{} addresses", addresses)];
+    if addresses > LOOKUP_TABLE_ADDRESSES_SHOWN {
+        notes.push(format!("Showing the first {}", LOOKUP_TABLE_ADDRESSES_SHOWN));
+    }
+    let kind = if deactivation_slot != ACTIVE_SLOT {
+        notes.push(format!("Deactivated at slot {}, closable once the slot leaves the slot hashes", deactivation_slot));
+        "Lookup Table (deactivated)"
+    } else if frozen {
+        "Lookup Table (frozen)"
+    } else {
+        "Lookup Table (active)"
+    };
+    Some(NativeAccount { kind: kind.to_string(), fields: reader.fields, notes })
+}
+
+/// Decodes a `ConfigKeys` list (short_vec length, then pubkey and signer flag per key)
+/// followed by the config data: validator info JSON, stake config, or raw bytes
+fn decode_config(data: &[u8]) -> Option<NativeAccount> {
+    let mut reader = Reader::new(data);
+    let mut keys = 0usize;
+    let mut len_bytes = 0;
+    for (i, byte) in data.iter().take(3).enumerate() {
+        keys |= ((byte & 0x7f) as usize) << (7 * i);
+        len_bytes = i + 1;
+        if byte & 0x80 == 0 {
+            break;
+        }
+    }
+    reader.take(len_bytes, "keys.len", keys.to_string())?;
+    for i in 0..keys {
+        reader.pubkey(&format!("keys[{}].pubkey", i))?;
+        reader.bool(&format!("keys[{}].signer", i))?;
+    }
+
+    let rest = data.get(reader.offset..)?;
+    let text = rest.get(8..)
+        .zip(rest.get(..8).map(|len| u64::from_le_bytes(len.try_into().unwrap()) as usize))
+        .and_then(|(bytes, len)| std::str::from_utf8(bytes.get(..len)?).ok());
+    let kind = if let Some(text) = text.filter(|t| !t.is_empty()) {
+        reader.take(8 + text.len(), "config_data", text.to_string())?;
+        if text.starts_with('{') { "Config (validator info)" } else { "Config" }
+    } else if rest.len() == 9 {
+        let rate = f64::from_le_bytes(rest[..8].try_into().unwrap());
+        reader.take(8, "warmup_cooldown_rate", rate.to_string())?;
+        reader.u8("slash_penalty")?;
+        "Config (stake config)"
+    } else {
+        "Config"
+    };
+    Some(NativeAccount { kind: kind.to_string(), fields: reader.fields, notes: Vec::new() })
+}
+
+/// Decodes an account of a native program, identified by its owner
+pub fn decode(data: &[u8], owner: &Pubkey) -> Option<NativeAccount> {
+    if *owner == system_program::id() {
+        decode_nonce(data)
+    } else if *owner == stake::program::id() {
+        decode_stake(data)
+    } else if *owner == vote::program::id() {
+        decode_vote(data)
+    } else if *owner == address_lookup_table::program::id() {
+        decode_lookup_table(data)
+    } else if *owner == config::program::id() {
+        decode_config(data)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+    use solana_sdk::address_lookup_table::state::{AddressLookupTable, LookupTableMeta};
+    use solana_sdk::hash::Hash;
+    use solana_sdk::nonce::state::{Data, DurableNonce, State, Versions};
+    use solana_sdk::stake::stake_flags::StakeFlags;
+    use solana_sdk::stake::state::{Authorized, Delegation, Lockup, Meta, Stake, StakeStateV2};
+    use solana_sdk::vote::state::{VoteInit, VoteState, VoteStateVersions};
+
+    fn field<'a>(account: &'a NativeAccount, name: &str) -> Option<&'a str> {
+        account.fields.iter().find(|f| f.name == name).map(|f| f.value.as_str())
+    }
+
+    #[test]
+    fn decodes_nonce() {
+        let authority = Pubkey::new_unique();
+        let durable_nonce = DurableNonce::from_blockhash(&Hash::new_unique());
+        let state = Versions::new(State::Initialized(Data::new(authority, durable_nonce, 5000)));
+        let data = bincode::serialize(&state).unwrap();
+
+        let account = decode(&data, &system_program::id()).unwrap();
+        assert_eq!(account.kind, "Nonce");
+        assert_eq!(field(&account, "durable_nonce"), Some(durable_nonce.as_hash().to_string().as_str()));
+        assert_eq!(field(&account, "lamports_per_signature"), Some("5000"));
+        assert!(decode(&data[..40], &system_program::id()).is_none());
+    }
+
+    #[test]
+    fn decodes_delegated_stake() {
+        let voter = Pubkey::new_unique();
+        let authorized = Authorized::auto(&Pubkey::new_unique());
+        let meta = Meta { rent_exempt_reserve: 2_282_880, authorized, lockup: Lockup::default() };
+        let stake = Stake { delegation: Delegation::new(&voter, 1_000_000, 10), credits_observed: 7 };
+        let mut data = bincode::serialize(&StakeStateV2::Stake(meta, stake, StakeFlags::empty())).unwrap();
+        data.resize(StakeStateV2::size_of(), 0);
+
+        let account = decode(&data, &stake::program::id()).unwrap();
+        assert_eq!(account.kind, "Stake (delegated)");
+        assert_eq!(account.notes, [format!("Delegated 1000000 lamports to {} from epoch 10", voter)]);
+        assert_eq!(field(&account, "stake.delegation.deactivation_epoch"), Some("none"));
+        assert_eq!(field(&account, "stake.credits_observed"), Some("7"));
+    }
+
+    #[test]
+    fn decodes_current_vote_state() {
+        let node = Pubkey::new_unique();
+        let init = VoteInit { node_pubkey: node, authorized_voter: node, authorized_withdrawer: node, commission: 7 };
+        let mut vote_state = VoteState::new(&init, &Default::default());
+        vote_state.process_next_vote_slot(42, 0, 0, false, false);
+        vote_state.increment_credits(3, 9);
+        let versions = VoteStateVersions::new_current(vote_state);
+        let mut data = vec![0; VoteState::size_of()];
+        VoteState::serialize(&versions, &mut data).unwrap();
+
+        let account = decode(&data, &vote::program::id()).unwrap();
+        assert_eq!(account.kind, "Vote");
+        assert_eq!(field(&account, "version"), Some("Current"));
+        assert_eq!(field(&account, "commission"), Some("7"));
+        assert_eq!(field(&account, "votes"), Some("1 votes, last slot 42"));
+        assert_eq!(field(&account, "epoch_credits"), Some("1 epochs, 9 credits as of epoch 3"));
+        assert_eq!(account.notes, ["Voting through epoch 3"]);
+    }
+
+    #[test]
+    fn decodes_lookup_table() {
+        let addresses = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let table = AddressLookupTable {
+            meta: LookupTableMeta { authority: None, ..LookupTableMeta::default() },
+            addresses: Cow::Borrowed(&addresses),
+        };
+        let data = table.serialize_for_tests().unwrap();
+
+        let account = decode(&data, &address_lookup_table::program::id()).unwrap();
+        assert_eq!(account.kind, "Lookup Table (frozen)");
+        assert_eq!(account.notes, ["2 addresses"]);
+        assert_eq!(field(&account, "addresses[1]"), Some(addresses[1].to_string().as_str()));
+    }
+
+    #[test]
+    fn decodes_config_accounts() {
+        let mut data = vec![1];
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.push(1);
+        let info = r#"{"name":"validator"}"#;
+        data.extend_from_slice(&(info.len() as u64).to_le_bytes());
+        data.extend_from_slice(info.as_bytes());
+        let account = decode(&data, &config::program::id()).unwrap();
+        assert_eq!(account.kind, "Config (validator info)");
+        assert_eq!(field(&account, "config_data"), Some(info));
+
+        let mut data = vec![0];
+        data.extend_from_slice(&0.25f64.to_le_bytes());
+        data.push(12);
+        let account = decode(&data, &config::program::id()).unwrap();
+        assert_eq!(account.kind, "Config (stake config)");
+        assert_eq!(field(&account, "slash_penalty"), Some("12"));
+    }
+}
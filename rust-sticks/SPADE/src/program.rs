@@ -0,0 +1,230 @@
+use std::fs;
+
+use anyhow::{anyhow, bail, Result};
+use chrono::DateTime;
+use solana_sdk::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+use solana_sdk::hash::hash;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{bpf_loader, bpf_loader_deprecated};
+
+use crate::elf;
+use crate::labels;
+use crate::AccountFetcher;
+
+/// The ProgramData account of an upgradeable program
+#[derive(Debug, Clone)]
+pub struct ProgramDataInfo {
+    pub address: Pubkey,
+    pub lamports: u64,
+    /// Slot the program was last deployed or upgraded in
+    pub deploy_slot: u64,
+    pub deploy_time: Option<i64>,
+    /// None once the program has been made immutable
+    pub upgrade_authority: Option<Pubkey>,
+    /// Bytes reserved for the executable, excluding the metadata header
+    pub allocated_len: usize,
+}
+
+/// Program-level metadata resolved from the program account and its loader
+#[derive(Debug, Clone)]
+pub struct ProgramInfo {
+    pub loader: Pubkey,
+    pub program_lamports: u64,
+    /// Present for programs owned by the upgradeable loader
+    pub programdata: Option<ProgramDataInfo>,
+    /// The executable with trailing zero padding stripped
+    pub elf: Vec<u8>,
+}
+
+/// Length of an ELF64 file from its header: the section header table, which the linker places
+/// last, or the program header table, whichever ends later
+fn elf_len(bytes: &[u8]) -> Option<usize> {
+    if !bytes.starts_with(b"\x7fELF") {
+        return None;
+    }
+    let u16_at = |offset: usize| Some(u16::from_le_bytes(bytes.get(offset..offset + 2)?.try_into().ok()?) as usize);
+    let u64_at = |offset: usize| usize::try_from(u64::from_le_bytes(bytes.get(offset..offset + 8)?.try_into().ok()?)).ok();
+    // Header values come from untrusted bytes: offsets near usize::MAX must not wrap
+    let table_end = |offset: usize, entry_size: usize, count: usize| {
+        u64_at(offset)?.checked_add(u16_at(entry_size)?.checked_mul(u16_at(count)?)?)
+    };
+    let program_headers = table_end(0x20, 0x36, 0x38)?;
+    let section_headers = table_end(0x28, 0x3a, 0x3c)?;
+    Some(program_headers.max(section_headers)).filter(|&len| len <= bytes.len())
+}
+
+/// Strips the zero padding that follows the executable in its allocation. The ELF header
+/// gives the exact length, since the file itself may end in zero bytes; other data is
+/// trimmed after its last non-zero byte
+pub fn trim_padding(bytes: &[u8]) -> &[u8] {
+    let end = elf_len(bytes)
+        .unwrap_or_else(|| bytes.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1));
+    &bytes[..end]
+}
+
+impl ProgramInfo {
+    /// Fetches the program account and, for upgradeable programs, its ProgramData account
+    pub fn fetch(fetcher: &mut AccountFetcher) -> Result<Self> {
+        let program_id = fetcher.program_id;
+        let program = fetcher.with_retry(|client| Ok(client.get_account(&program_id)?))?;
+        if !program.executable {
+            bail!("{} is not an executable program account", program_id);
+        }
+
+        if program.owner == bpf_loader::id() || program.owner == bpf_loader_deprecated::id() {
+            return Ok(Self {
+                loader: program.owner,
+                program_lamports: program.lamports,
+                programdata: None,
+                elf: trim_padding(&program.data).to_vec(),
+            });
+        }
+
+        if program.owner != bpf_loader_upgradeable::id() {
+            bail!("Unsupported loader {}", labels::describe(&program.owner));
+        }
+
+        let programdata_address = match bincode::deserialize(&program.data)? {
+            UpgradeableLoaderState::Program { programdata_address } => programdata_address,
+            state => bail!("Unexpected program account state {:?}", state),
+        };
+
+        let programdata = fetcher.with_retry(|client| Ok(client.get_account(&programdata_address)?))
+            .map_err(|e| anyhow!("ProgramData account {} unavailable (program closed?): {}", programdata_address, e))?;
+        let metadata_len = UpgradeableLoaderState::size_of_programdata_metadata();
+        let (deploy_slot, upgrade_authority) = match bincode::deserialize(&programdata.data)? {
+            UpgradeableLoaderState::ProgramData { slot, upgrade_authority_address } => (slot, upgrade_authority_address),
+            state => bail!("Unexpected ProgramData account state {:?}", state),
+        };
+        let executable = programdata.data.get(metadata_len..).unwrap_or_default();
+        let deploy_time = fetcher.with_retry(|client| Ok(client.get_block_time(deploy_slot)?)).ok();
+
+        Ok(Self {
+            loader: program.owner,
+            program_lamports: program.lamports,
+            programdata: Some(ProgramDataInfo {
+                address: programdata_address,
+                lamports: programdata.lamports,
+                deploy_slot,
+                deploy_time,
+                upgrade_authority,
+                allocated_len: executable.len(),
+            }),
+            elf: trim_padding(executable).to_vec(),
+        })
+    }
+
+    /// Lamports held by the program and ProgramData accounts
+    pub fn lamports_locked(&self) -> u64 {
+        self.program_lamports + self.programdata.as_ref().map_or(0, |pd| pd.lamports)
+    }
+
+    /// Prints the program metadata as shown under the intro banner
+    pub fn print(&self) {
+        println!("Loader:            {}", labels::describe(&self.loader));
+        match &self.programdata {
+            Some(pd) => {
+                println!("ProgramData:       {}", pd.address);
+                match pd.upgrade_authority {
+                    Some(authority) => println!("Upgrade authority: {}", labels::describe(&authority)),
+                    None => println!("Upgrade authority: none (immutable)"),
+                }
+                let time = pd.deploy_time
+                    .and_then(|t| DateTime::from_timestamp(t, 0))
+                    .map(|t| format!(" ({})", t.format("%Y-%m-%d %H:%M UTC")))
+                    .unwrap_or_default();
+                println!("Last deployed:     slot {}{}", pd.deploy_slot, time);
+                println!("Executable size:   {} bytes ({} allocated, {:.1}% used)",
+                    self.elf.len(), pd.allocated_len,
+                    100.0 * self.elf.len() as f64 / pd.allocated_len.max(1) as f64);
+            }
+            None => {
+                println!("Upgrade authority: none (non-upgradeable loader)");
+                println!("Executable size:   {} bytes", self.elf.len());
+            }
+        }
+        println!("SOL locked:        {:.6} SOL", self.lamports_locked() as f64 / LAMPORTS_PER_SOL as f64);
+        elf::extract_metadata(&self.elf).print();
+    }
+}
+
+/// Hex sha256 of the executable bytes
+pub fn elf_hash(elf: &[u8]) -> String {
+    hex::encode(hash(elf).to_bytes())
+}
+
+/// Writes the deployed executable to `path` (default `<PROGRAM_ID>.so`)
+pub fn dump_program(fetcher: &mut AccountFetcher, path: Option<&str>) -> Result<()> {
+    let info = ProgramInfo::fetch(fetcher)?;
+    let path = path.map(String::from).unwrap_or_else(|| format!("{}.so", fetcher.program_id));
+    fs::write(&path, &info.elf)?;
+    println!("Wrote {} bytes to {}", info.elf.len(), path);
+    println!("sha256: {}", elf_hash(&info.elf));
+    Ok(())
+}
+
+/// Compares the deployed executable with a local build, ignoring trailing zero padding
+pub fn verify_program(fetcher: &mut AccountFetcher, so_path: &str) -> Result<()> {
+    let info = ProgramInfo::fetch(fetcher)?;
+    let local = fs::read(so_path).map_err(|e| anyhow!("Failed to read {}: {}", so_path, e))?;
+    let local = trim_padding(&local);
+
+    let (deployed_hash, local_hash) = (elf_hash(&info.elf), elf_hash(local));
+    println!("Deployed: {} ({} bytes)", deployed_hash, info.elf.len());
+    println!("Local:    {} ({} bytes) {}", local_hash, local.len(), so_path);
+
+    if deployed_hash == local_hash {
+        println!("\n✅ Deployed program matches the local build");
+        return Ok(());
+    }
+
+    match info.elf.iter().zip(local).position(|(a, b)| a != b) {
+        Some(offset) => println!("First difference at offset {:#x}", offset),
+        None => println!("One binary is a prefix of the other ({} vs {} bytes)", info.elf.len(), local.len()),
+    }
+    bail!("Deployed program does not match {}", so_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ELF64 header with one program header at 0x40 and two section headers at 0x100,
+    /// so the file is 0x180 bytes long
+    fn elf() -> Vec<u8> {
+        let mut elf = vec![0u8; 0x180];
+        elf[..4].copy_from_slice(b"\x7fELF");
+        elf[0x20..0x28].copy_from_slice(&0x40u64.to_le_bytes());
+        elf[0x28..0x30].copy_from_slice(&0x100u64.to_le_bytes());
+        elf[0x36..0x38].copy_from_slice(&0x38u16.to_le_bytes());
+        elf[0x38..0x3a].copy_from_slice(&1u16.to_le_bytes());
+        elf[0x3a..0x3c].copy_from_slice(&0x40u16.to_le_bytes());
+        elf[0x3c..0x3e].copy_from_slice(&2u16.to_le_bytes());
+        elf[0x90] = 0xaa;
+        elf
+    }
+
+    #[test]
+    fn keeps_trailing_zeros_inside_the_elf() {
+        let mut padded = elf();
+        padded.extend_from_slice(&[0; 0x80]);
+        assert_eq!(elf_len(&padded), Some(0x180));
+        assert_eq!(trim_padding(&padded), elf().as_slice());
+    }
+
+    #[test]
+    fn falls_back_to_last_non_zero_byte() {
+        assert_eq!(trim_padding(&[1, 2, 0, 3, 0, 0]), [1, 2, 0, 3]);
+        assert!(trim_padding(&[0; 8]).is_empty());
+
+        // Header pointing past the data, e.g. a truncated dump
+        let truncated = &elf()[..0x100];
+        assert_eq!(elf_len(truncated), None);
+        assert_eq!(trim_padding(truncated).len(), 0x91);
+
+        let mut crafted = elf();
+        crafted[0x28..0x30].copy_from_slice(&(u64::MAX - 0x10).to_le_bytes());
+        assert_eq!(elf_len(&crafted), None);
+    }
+}
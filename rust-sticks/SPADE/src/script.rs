@@ -0,0 +1,285 @@
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use rhai::{Array, Blob, Dynamic, Engine, EvalAltResult, Map, Scope, INT};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::decoder::{DecodedAccount, DecoderRegistry};
+use crate::labels;
+use crate::layout::{self, Field};
+use crate::snapshot::Snapshot;
+use crate::token;
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// An account as seen by scripts: raw data plus whatever the decoder registry made of it
+#[derive(Clone)]
+struct ScriptAccount(Rc<AccountData>);
+
+struct AccountData {
+    pubkey: Pubkey,
+    owner: Pubkey,
+    lamports: u64,
+    data: Vec<u8>,
+    decoded: Option<DecodedAccount>,
+}
+
+impl ScriptAccount {
+    fn bytes(&self, offset: INT, len: usize) -> ScriptResult<&[u8]> {
+        usize::try_from(offset).ok()
+            .and_then(|offset| self.0.data.get(offset..offset.checked_add(len)?))
+            .ok_or_else(|| format!("{} bytes at offset {} are out of bounds for {} ({} bytes)",
+                len, offset, self.0.pubkey, self.0.data.len()).into())
+    }
+
+    fn read<const N: usize>(&self, offset: INT) -> ScriptResult<[u8; N]> {
+        Ok(self.bytes(offset, N)?.try_into().unwrap())
+    }
+
+    fn discriminator(&self) -> String {
+        self.0.data.get(..8).map(hex::encode).unwrap_or_default()
+    }
+
+    /// Decoded type name, else the discriminator, as grouped by the account type analysis
+    fn type_name(&self) -> String {
+        match &self.0.decoded {
+            Some(decoded) => decoded.type_name.clone(),
+            None if self.0.data.len() >= 8 => self.discriminator(),
+            None => format!("Unknown ({} bytes)", self.0.data.len()),
+        }
+    }
+
+    fn field(&self, name: &str) -> Dynamic {
+        self.0.decoded.as_ref()
+            .and_then(|decoded| decoded.fields.iter().rev().find(|f| f.name == name))
+            .map(|f| field_value(&self.0.data, f))
+            .unwrap_or(Dynamic::UNIT)
+    }
+
+    fn fields(&self) -> Map {
+        self.0.decoded.iter()
+            .flat_map(|decoded| &decoded.fields)
+            .map(|f| (f.name.as_str().into(), field_value(&self.0.data, f)))
+            .collect()
+    }
+}
+
+/// A decoded field as a script value, read back from the bytes it covers: integers as `INT`
+/// (as a decimal string when outside the i64 range, e.g. a u64 above `i64::MAX`), booleans,
+/// pubkeys as bare base58 and Borsh strings as their text. Anything else is the displayed value
+fn field_value(data: &[u8], field: &Field) -> Dynamic {
+    let Some(bytes) = data.get(field.offset..field.end()) else {
+        return Dynamic::from(field.value.clone());
+    };
+    if let Some((_, pubkey)) = layout::pubkey_fields(data, std::slice::from_ref(field)).first() {
+        return Dynamic::from(pubkey.to_string());
+    }
+    if field.len == 1 && (field.value == "true" || field.value == "false") {
+        return Dynamic::from(bytes[0] != 0);
+    }
+    if field.value.starts_with('"') {
+        if let Some((text, len)) = layout::read_string(data, field.offset) {
+            if len == field.len {
+                return Dynamic::from(text);
+            }
+        }
+    }
+    // Decoders may annotate an integer, e.g. `1500000 (1.5)`: keep it when it is the one stored
+    let shown = field.value.split(' ').next().unwrap_or_default();
+    if let (Ok(value), 1..=16) = (shown.parse::<i128>(), field.len) {
+        let mut unsigned = [0u8; 16];
+        unsigned[..field.len].copy_from_slice(bytes);
+        let mut signed = unsigned;
+        if bytes[field.len - 1] & 0x80 != 0 {
+            signed[field.len..].fill(0xff);
+        }
+        if value == i128::from_le_bytes(signed) || value as u128 == u128::from_le_bytes(unsigned) {
+            return INT::try_from(value).map(Dynamic::from).unwrap_or_else(|_| Dynamic::from(shown.to_string()));
+        }
+    }
+    Dynamic::from(field.value.clone())
+}
+
+/// Wraps a snapshot's accounts for scripts, decoding each one
+fn to_array(snapshot: Snapshot, registry: &DecoderRegistry) -> Array {
+    snapshot.accounts.into_iter()
+        .map(|account| {
+            let decoded = registry.decode(&account.data, &snapshot.program_id);
+            Dynamic::from(ScriptAccount(Rc::new(AccountData {
+                pubkey: account.pubkey,
+                owner: snapshot.program_id,
+                lamports: account.lamports,
+                data: account.data,
+                decoded,
+            })))
+        })
+        .collect()
+}
+
+fn to_int(value: u64, what: &str) -> ScriptResult<INT> {
+    INT::try_from(value).map_err(|_| format!("{} {} does not fit a script integer", what, value).into())
+}
+
+fn parse_pubkey(pubkey: &str) -> ScriptResult<Pubkey> {
+    Pubkey::from_str(pubkey).map_err(|e| format!("invalid pubkey {}: {}", pubkey, e).into())
+}
+
+/// Builds the engine with the `Account` type and the helper functions scripts can call
+fn build_engine(registry: Rc<DecoderRegistry>) -> Engine {
+    let mut engine = Engine::new();
+
+    engine.register_type_with_name::<ScriptAccount>("Account")
+        .register_get("pubkey", |a: &mut ScriptAccount| a.0.pubkey.to_string())
+        .register_get("owner", |a: &mut ScriptAccount| a.0.owner.to_string())
+        .register_get("lamports", |a: &mut ScriptAccount| to_int(a.0.lamports, "lamports"))
+        .register_get("size", |a: &mut ScriptAccount| a.0.data.len() as INT)
+        .register_get("data", |a: &mut ScriptAccount| -> Blob { a.0.data.clone() })
+        .register_get("discriminator", |a: &mut ScriptAccount| a.discriminator())
+        .register_get("type_name", |a: &mut ScriptAccount| a.type_name())
+        .register_get("decoder", |a: &mut ScriptAccount| {
+            a.0.decoded.as_ref().map(|d| Dynamic::from(d.decoder.clone())).unwrap_or(Dynamic::UNIT)
+        })
+        .register_get("fields", |a: &mut ScriptAccount| a.fields())
+        .register_get("notes", |a: &mut ScriptAccount| -> Array {
+            a.0.decoded.iter().flat_map(|d| &d.notes).map(|n| Dynamic::from(n.clone())).collect()
+        })
+        .register_indexer_get(|a: &mut ScriptAccount, name: &str| a.field(name))
+        .register_fn("field", |a: &mut ScriptAccount, name: &str| a.field(name))
+        .register_fn("u8", |a: &mut ScriptAccount, offset: INT| -> ScriptResult<INT> {
+            Ok(a.read::<1>(offset)?[0] as INT)
+        })
+        .register_fn("u16", |a: &mut ScriptAccount, offset: INT| -> ScriptResult<INT> {
+            Ok(u16::from_le_bytes(a.read(offset)?) as INT)
+        })
+        .register_fn("u32", |a: &mut ScriptAccount, offset: INT| -> ScriptResult<INT> {
+            Ok(u32::from_le_bytes(a.read(offset)?) as INT)
+        })
+        .register_fn("u64", |a: &mut ScriptAccount, offset: INT| {
+            to_int(u64::from_le_bytes(a.read(offset)?), &format!("u64 at offset {} of {}", offset, a.0.pubkey))
+        })
+        .register_fn("i64", |a: &mut ScriptAccount, offset: INT| -> ScriptResult<INT> {
+            Ok(i64::from_le_bytes(a.read(offset)?) as INT)
+        })
+        .register_fn("pubkey", |a: &mut ScriptAccount, offset: INT| -> ScriptResult<String> {
+            Ok(Pubkey::new_from_array(a.read(offset)?).to_string())
+        })
+        .register_fn("string", |a: &mut ScriptAccount, offset: INT| -> ScriptResult<String> {
+            usize::try_from(offset).ok()
+                .and_then(|offset| layout::read_string(&a.0.data, offset))
+                .map(|(text, _)| text)
+                .ok_or_else(|| format!("no Borsh string at offset {} of {}", offset, a.0.pubkey).into())
+        })
+        .register_fn("bytes", |a: &mut ScriptAccount, offset: INT, len: INT| -> ScriptResult<Blob> {
+            Ok(a.bytes(offset, usize::try_from(len).map_err(|_| "negative length")?)?.to_vec())
+        });
+
+    engine.register_fn("hex", |bytes: Blob| hex::encode(bytes))
+        .register_fn("label", |pubkey: &str| -> ScriptResult<String> {
+            Ok(labels::describe(&parse_pubkey(pubkey)?))
+        })
+        .register_fn("format_amount", |amount: INT, decimals: INT| {
+            token::format_amount(amount.max(0) as u64, decimals.clamp(0, u8::MAX as INT) as u8)
+        });
+
+    let snapshot_registry = registry.clone();
+    engine.register_fn("load_snapshot", move |path: &str| -> ScriptResult<Array> {
+        let snapshot = Snapshot::load(path).map_err(|e| e.to_string())?;
+        Ok(to_array(snapshot, &snapshot_registry))
+    });
+    engine.register_fn("fetch_accounts", move |program_id: &str| -> ScriptResult<Array> {
+        let snapshot = Snapshot::from_source(program_id).map_err(|e| e.to_string())?;
+        Ok(to_array(snapshot, &registry))
+    });
+
+    engine
+}
+
+/// Runs a Rhai script against a program's accounts (fetched over RPC) or a `.spade` snapshot.
+/// The script sees `accounts`, `program_id` and `slot`. Flags: `--idl FILE`, `--decoders DIR`
+/// (defaults to `SPADE_DECODERS`)
+pub fn run_script(path: &str, args: &[String]) -> Result<()> {
+    let registry = Rc::new(DecoderRegistry::from_args(args)?);
+    let engine = build_engine(registry.clone());
+
+    let mut scope = Scope::new();
+    match args.first().filter(|arg| !arg.starts_with("--")) {
+        Some(source) => {
+            let snapshot = Snapshot::from_source(source)?;
+            println!("📜 Running {} on {} accounts of {} (slot {})",
+                path, snapshot.accounts.len(), snapshot.program_id, snapshot.slot);
+            scope.push_constant("program_id", snapshot.program_id.to_string());
+            scope.push_constant("slot", to_int(snapshot.slot, "slot").map_err(|e| anyhow!("{}", e))?);
+            scope.push_constant("accounts", to_array(snapshot, &registry));
+        }
+        None => {
+            scope.push_constant("program_id", String::new());
+            scope.push_constant("slot", 0 as INT);
+            scope.push_constant("accounts", Array::new());
+        }
+    }
+
+    engine.run_file_with_scope(&mut scope, PathBuf::from(path))
+        .map_err(|e| anyhow!("{}: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::SnapshotAccount;
+
+    #[test]
+    fn field_values_are_typed() {
+        let key = Pubkey::new_unique();
+        let mut data = key.to_bytes().to_vec();
+        data.extend_from_slice(&1_500_000u64.to_le_bytes());
+        data.extend_from_slice(&u64::MAX.to_le_bytes());
+        data.extend_from_slice(&(-3i32).to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(b"hi");
+
+        let value = |offset, len, shown: &str| field_value(&data, &Field::new(offset, len, "f", shown));
+        assert_eq!(value(0, 32, &format!("{} (Vault)", key)).into_string().unwrap(), key.to_string());
+        assert_eq!(value(32, 8, "1500000 (1.5)").as_int().unwrap(), 1_500_000);
+        assert_eq!(value(40, 8, &u64::MAX.to_string()).into_string().unwrap(), u64::MAX.to_string());
+        assert_eq!(value(48, 4, "-3").as_int().unwrap(), -3);
+        assert!(value(52, 1, "true").as_bool().unwrap());
+        assert_eq!(value(53, 6, "\"hi\"").into_string().unwrap(), "hi");
+        // A number that is not the stored integer stays the displayed text
+        assert_eq!(value(32, 8, "7 days").into_string().unwrap(), "7 days");
+        assert_eq!(value(40, 32, "00ff").into_string().unwrap(), "00ff");
+    }
+
+    #[test]
+    fn scripts_read_raw_account_bytes() {
+        let program_id = Pubkey::new_unique();
+        let mut data = vec![7u8];
+        data.extend_from_slice(&42u64.to_le_bytes());
+        data.extend_from_slice(&u64::MAX.to_le_bytes());
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(b"abc");
+        let snapshot = Snapshot {
+            program_id,
+            slot: 1,
+            captured_at: 0,
+            accounts: vec![SnapshotAccount { pubkey: Pubkey::new_unique(), lamports: 10, data }],
+        };
+
+        let registry = Rc::new(DecoderRegistry::with_builtin(None));
+        let engine = build_engine(registry.clone());
+        let mut scope = Scope::new();
+        scope.push_constant("accounts", to_array(snapshot, &registry));
+        let mut eval = |script: &str| engine.eval_with_scope::<Dynamic>(&mut scope, script);
+
+        assert_eq!(eval("accounts[0].u8(0)").unwrap().as_int().unwrap(), 7);
+        assert_eq!(eval("accounts[0].u64(1)").unwrap().as_int().unwrap(), 42);
+        assert_eq!(eval("hex(accounts[0].bytes(1, 2))").unwrap().into_string().unwrap(), "2a00");
+        assert_eq!(eval("accounts[0].string(17)").unwrap().into_string().unwrap(), "abc");
+        assert_eq!(eval("accounts[0].owner").unwrap().into_string().unwrap(), program_id.to_string());
+        assert!(eval("accounts[0].u64(20)").unwrap_err().to_string().contains("out of bounds"));
+        assert!(eval("accounts[0].bytes(-1, 1)").unwrap_err().to_string().contains("out of bounds"));
+        assert!(eval("accounts[0].u64(9)").unwrap_err().to_string().contains("does not fit"));
+    }
+}
@@ -0,0 +1,635 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use aho_corasick::AhoCorasick;
+use anyhow::{anyhow, bail, Result};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::AccountFetcher;
+
+/// What a single search term matches against
+#[derive(Debug, Clone)]
+enum TermKind {
+    /// Exact bytes, `None` entries are `??` wildcards
+    Bytes(Vec<Option<u8>>),
+    /// Integer of `width` bytes whose decoded value falls in `range`
+    Range {
+        width: usize,
+        big_endian: bool,
+        range: IntRange,
+    },
+}
+
+/// Inclusive bounds of a range term, in the signedness of its integer type so that u128
+/// values above `i128::MAX` compare correctly
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IntRange {
+    Signed(i128, i128),
+    Unsigned(u128, u128),
+}
+
+/// One parsed term of a search query, e.g. `u64:1000000@72`
+#[derive(Debug, Clone)]
+struct SearchTerm {
+    label: String,
+    kind: TermKind,
+    offset: Option<usize>,
+}
+
+/// A parsed search query: the terms to look for and an optional discriminator filter
+#[derive(Debug, Default)]
+struct SearchQuery {
+    terms: Vec<SearchTerm>,
+    discriminator: Option<Vec<u8>>,
+}
+
+/// A single match of a term inside an account
+#[derive(Debug, Clone, Copy)]
+struct Hit {
+    term: usize,
+    offset: usize,
+}
+
+/// Prints the query syntax accepted by the pattern search
+fn print_syntax() {
+    println!("Terms are separated by spaces, all terms are searched at once:");
+    println!("  0102ff / hex:01??ff    hex bytes, ?? matches any byte");
+    println!("  pk:<BASE58>            32-byte public key (bare pubkeys work too, bare hex wins)");
+    println!("  str:hello              UTF-8 string, taken as is (user@example.com is one string)");
+    println!("  str:\"a b\"@8            quoted string, to keep spaces or anchor it to an offset");
+    println!("  u64:1000000            little-endian integer (u8..u128, i8..i128)");
+    println!("  u32be:5                big-endian integer");
+    println!("  u64:100..200           integer range (inclusive)");
+    println!("  <term>@72              only match at offset 72");
+    println!("  type:<16 hex chars>    only search accounts with this discriminator");
+}
+
+/// Splits a query on whitespace while keeping double-quoted sections together. The quotes
+/// are kept so terms can tell quoted values apart
+fn tokenize(input: &str) -> Result<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in input.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(c);
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+
+    if in_quotes {
+        bail!("Unterminated quote in query");
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    Ok(tokens)
+}
+
+/// Parses hex with `??` wildcards into a byte pattern
+fn parse_hex_pattern(hex_str: &str) -> Result<Vec<Option<u8>>> {
+    let hex_str = hex_str.trim_start_matches("0x");
+    if !hex_str.len().is_multiple_of(2) {
+        bail!("Hex pattern '{}' has an odd number of digits", hex_str);
+    }
+
+    let mut pattern = Vec::with_capacity(hex_str.len() / 2);
+    for pair in hex_str.as_bytes().chunks(2) {
+        let pair = std::str::from_utf8(pair)?;
+        if pair == "??" {
+            pattern.push(None);
+        } else {
+            pattern.push(Some(u8::from_str_radix(pair, 16)
+                .map_err(|_| anyhow!("Invalid hex byte '{}'", pair))?));
+        }
+    }
+
+    if pattern.iter().all(Option::is_none) {
+        bail!("Pattern '{}' needs at least one fixed byte", hex_str);
+    }
+
+    Ok(pattern)
+}
+
+/// Parses an integer type name such as `u64` or `i32be` into (width, signed, big_endian)
+fn parse_int_type(name: &str) -> Option<(usize, bool, bool)> {
+    let (name, big_endian) = match name.strip_suffix("be") {
+        Some(stripped) => (stripped, true),
+        None => (name.strip_suffix("le").unwrap_or(name), false),
+    };
+    let signed = match name.chars().next()? {
+        'u' => false,
+        'i' => true,
+        _ => return None,
+    };
+    let width = match &name[1..] {
+        "8" => 1,
+        "16" => 2,
+        "32" => 4,
+        "64" => 8,
+        "128" => 16,
+        _ => return None,
+    };
+    Some((width, signed, big_endian))
+}
+
+/// Parses an exact integer of the given width and signedness into its 16 little-endian bytes
+fn parse_int(value: &str, width: usize, signed: bool) -> Result<[u8; 16]> {
+    let bits = (width * 8) as u32;
+    let invalid = || anyhow!("Invalid integer '{}'", value);
+    let fits = if signed {
+        let parsed = value.parse::<i128>().map_err(|_| invalid())?;
+        let fits = bits == 128 || (-(1i128 << (bits - 1))..1i128 << (bits - 1)).contains(&parsed);
+        fits.then_some(parsed.to_le_bytes())
+    } else {
+        let parsed = value.parse::<u128>().map_err(|_| invalid())?;
+        (bits == 128 || parsed >> bits == 0).then_some(parsed.to_le_bytes())
+    };
+    fits.ok_or_else(|| anyhow!("Value {} does not fit in {} bytes", value, width))
+}
+
+/// Encodes the low `width` bytes of a little-endian integer with the requested endianness
+fn encode_int(le: [u8; 16], width: usize, big_endian: bool) -> Vec<u8> {
+    let mut bytes = le[..width].to_vec();
+    if big_endian {
+        bytes.reverse();
+    }
+    bytes
+}
+
+/// Widens `bytes` to 16 little-endian bytes, sign-extending when `signed`
+fn widen(bytes: &[u8], signed: bool, big_endian: bool) -> [u8; 16] {
+    let mut buf = [0u8; 16];
+    for (i, b) in bytes.iter().enumerate() {
+        buf[if big_endian { bytes.len() - 1 - i } else { i }] = *b;
+    }
+    if signed && buf[bytes.len() - 1] & 0x80 != 0 {
+        buf[bytes.len()..].fill(0xff);
+    }
+    buf
+}
+
+impl IntRange {
+    /// Parses `min..max` (either side may be empty) for an integer type. Bounds must fit the
+    /// type, like single values
+    fn parse(min: &str, max: &str, width: usize, signed: bool) -> Result<Self> {
+        let bound = |text: &str| -> Result<Option<[u8; 16]>> {
+            if text.is_empty() {
+                return Ok(None);
+            }
+            parse_int(text, width, signed).map(Some)
+        };
+        let (low, high) = (bound(min)?, bound(max)?);
+        let range = if signed {
+            IntRange::Signed(low.map_or(i128::MIN, i128::from_le_bytes), high.map_or(i128::MAX, i128::from_le_bytes))
+        } else {
+            IntRange::Unsigned(low.map_or(0, u128::from_le_bytes), high.map_or(u128::MAX, u128::from_le_bytes))
+        };
+        let empty = match range {
+            IntRange::Signed(min, max) => min > max,
+            IntRange::Unsigned(min, max) => min > max,
+        };
+        if empty {
+            bail!("Empty range '{}..{}'", min, max);
+        }
+        Ok(range)
+    }
+
+    /// Whether the integer in `bytes` falls in the range
+    fn contains(&self, bytes: &[u8], big_endian: bool) -> bool {
+        match *self {
+            IntRange::Signed(min, max) => {
+                (min..=max).contains(&i128::from_le_bytes(widen(bytes, true, big_endian)))
+            }
+            IntRange::Unsigned(min, max) => {
+                (min..=max).contains(&u128::from_le_bytes(widen(bytes, false, big_endian)))
+            }
+        }
+    }
+}
+
+/// Splits `value@offset` into the value and offset. A quoted value is unquoted and may be
+/// followed by `@offset`; unquoted values are split at their last `@` only if `anchorable`
+fn split_offset(value: &str, anchorable: bool) -> Result<(String, Option<usize>)> {
+    let parse_offset = |off: &str| off.parse::<usize>().map_err(|_| anyhow!("Invalid offset '{}' in '{}'", off, value));
+    if let Some(quoted) = value.strip_prefix('"') {
+        let end = quoted.find('"').ok_or_else(|| anyhow!("Unterminated quote in '{}'", value))?;
+        let offset = match &quoted[end + 1..] {
+            "" => None,
+            rest => match rest.strip_prefix('@') {
+                Some(off) => Some(parse_offset(off)?),
+                None => bail!("Unexpected '{}' after quoted value in '{}'", rest, value),
+            },
+        };
+        return Ok((quoted[..end].to_string(), offset));
+    }
+    match value.rsplit_once('@') {
+        Some((body, off)) if anchorable => Ok((body.to_string(), Some(parse_offset(off)?))),
+        _ => Ok((value.to_string(), None)),
+    }
+}
+
+fn pubkey_pattern(pubkey: &Pubkey) -> Vec<Option<u8>> {
+    pubkey.to_bytes().iter().map(|b| Some(*b)).collect()
+}
+
+/// Parses a single term such as `u64:5@8`, `pk:...`, `str:"a b"@4` or `01??02`. The term
+/// type is read first so `@` inside a string is never taken for an offset
+fn parse_term(token: &str) -> Result<SearchTerm> {
+    let typed = token.split_once(':').filter(|(ty, _)| !ty.starts_with('"'));
+    let (kind, offset) = match typed {
+        Some(("str", value)) => {
+            let (value, offset) = split_offset(value, false)?;
+            if value.is_empty() {
+                bail!("Empty string term");
+            }
+            (TermKind::Bytes(value.bytes().map(Some).collect()), offset)
+        }
+        Some((ty, value)) => {
+            let (value, offset) = split_offset(value, true)?;
+            let kind = match ty {
+                "hex" => TermKind::Bytes(parse_hex_pattern(&value)?),
+                "pk" => TermKind::Bytes(pubkey_pattern(&Pubkey::from_str(&value)
+                    .map_err(|_| anyhow!("Invalid pubkey '{}'", value))?)),
+                _ => {
+                    let (width, signed, big_endian) = parse_int_type(ty)
+                        .ok_or_else(|| anyhow!("Unknown term type '{}'", ty))?;
+                    match value.split_once("..") {
+                        Some((min, max)) => TermKind::Range { width, big_endian, range: IntRange::parse(min, max, width, signed)? },
+                        None => TermKind::Bytes(encode_int(parse_int(&value, width, signed)?, width, big_endian)
+                            .into_iter().map(Some).collect()),
+                    }
+                }
+            };
+            (kind, offset)
+        }
+        None => {
+            let (body, offset) = split_offset(token, true)?;
+            // Hex wins: 32+ hex digits can also be valid base58 (`1111...` is the System Program)
+            let kind = match parse_hex_pattern(&body) {
+                Ok(pattern) => TermKind::Bytes(pattern),
+                Err(e) => match Pubkey::from_str(&body) {
+                    Ok(pubkey) if body.len() >= 32 => TermKind::Bytes(pubkey_pattern(&pubkey)),
+                    _ => return Err(e),
+                },
+            };
+            (kind, offset)
+        }
+    };
+
+    Ok(SearchTerm { label: token.to_string(), kind, offset })
+}
+
+/// Parses a full query line into terms and filters
+fn parse_query(input: &str) -> Result<SearchQuery> {
+    let mut query = SearchQuery::default();
+
+    for token in tokenize(input)? {
+        if let Some(disc) = token.strip_prefix("type:") {
+            let disc = hex::decode(disc)?;
+            if disc.len() != 8 {
+                bail!("Discriminator must be 8 bytes, got {}", disc.len());
+            }
+            query.discriminator = Some(disc);
+        } else {
+            query.terms.push(parse_term(&token)?);
+        }
+    }
+
+    if query.terms.is_empty() {
+        bail!("Query has no search terms");
+    }
+
+    Ok(query)
+}
+
+/// Returns true if `pattern` matches `data` starting at `offset`
+fn matches_at(pattern: &[Option<u8>], data: &[u8], offset: usize) -> bool {
+    offset + pattern.len() <= data.len()
+        && pattern.iter()
+            .zip(&data[offset..])
+            .all(|(p, b)| p.is_none_or(|p| p == *b))
+}
+
+/// Finds the longest run of fixed bytes in a pattern, returning (start, bytes)
+fn longest_literal(pattern: &[Option<u8>]) -> (usize, Vec<u8>) {
+    let mut best = (0, Vec::new());
+    let mut start = 0;
+    let mut run = Vec::new();
+
+    for (i, byte) in pattern.iter().enumerate() {
+        match byte {
+            Some(b) => {
+                if run.is_empty() {
+                    start = i;
+                }
+                run.push(*b);
+                if run.len() > best.1.len() {
+                    best = (start, run.clone());
+                }
+            }
+            None => run.clear(),
+        }
+    }
+
+    best
+}
+
+/// Compiled form of a query: one Aho-Corasick automaton for all unanchored byte terms
+struct SearchEngine {
+    terms: Vec<SearchTerm>,
+    discriminator: Option<Vec<u8>>,
+    automaton: Option<AhoCorasick>,
+    // For each automaton pattern: the (term index, literal start within term) pairs using it
+    needle_terms: Vec<Vec<(usize, usize)>>,
+}
+
+impl SearchEngine {
+    fn new(query: SearchQuery) -> Result<Self> {
+        let mut needles: Vec<Vec<u8>> = Vec::new();
+        let mut needle_terms: Vec<Vec<(usize, usize)>> = Vec::new();
+
+        for (i, term) in query.terms.iter().enumerate() {
+            if let (TermKind::Bytes(pattern), None) = (&term.kind, term.offset) {
+                let (start, literal) = longest_literal(pattern);
+                match needles.iter().position(|n| *n == literal) {
+                    Some(idx) => needle_terms[idx].push((i, start)),
+                    None => {
+                        needles.push(literal);
+                        needle_terms.push(vec![(i, start)]);
+                    }
+                }
+            }
+        }
+
+        let automaton = if needles.is_empty() {
+            None
+        } else {
+            Some(AhoCorasick::new(&needles)?)
+        };
+
+        Ok(Self {
+            terms: query.terms,
+            discriminator: query.discriminator,
+            automaton,
+            needle_terms,
+        })
+    }
+
+    /// Returns every hit in `data`, sorted by offset
+    fn search(&self, data: &[u8]) -> Vec<Hit> {
+        if let Some(disc) = &self.discriminator {
+            if !data.starts_with(disc) {
+                return Vec::new();
+            }
+        }
+
+        let mut hits = Vec::new();
+
+        if let Some(automaton) = &self.automaton {
+            for m in automaton.find_overlapping_iter(data) {
+                for &(term, literal_start) in &self.needle_terms[m.pattern().as_usize()] {
+                    if m.start() < literal_start {
+                        continue;
+                    }
+                    let offset = m.start() - literal_start;
+                    if let TermKind::Bytes(pattern) = &self.terms[term].kind {
+                        if matches_at(pattern, data, offset) {
+                            hits.push(Hit { term, offset });
+                        }
+                    }
+                }
+            }
+        }
+
+        for (term_idx, term) in self.terms.iter().enumerate() {
+            match (&term.kind, term.offset) {
+                (TermKind::Bytes(pattern), Some(offset)) => {
+                    if matches_at(pattern, data, offset) {
+                        hits.push(Hit { term: term_idx, offset });
+                    }
+                }
+                (TermKind::Range { width, big_endian, range }, offset) => {
+                    let in_range = |off: usize| {
+                        off + width <= data.len() && range.contains(&data[off..off + width], *big_endian)
+                    };
+                    match offset {
+                        Some(off) => {
+                            if in_range(off) {
+                                hits.push(Hit { term: term_idx, offset: off });
+                            }
+                        }
+                        None => {
+                            for off in 0..data.len().saturating_sub(width - 1) {
+                                if in_range(off) {
+                                    hits.push(Hit { term: term_idx, offset: off });
+                                }
+                            }
+                        }
+                    }
+                }
+                (TermKind::Bytes(_), None) => {}
+            }
+        }
+
+        hits.sort_by_key(|h| (h.offset, h.term));
+        hits
+    }
+}
+
+/// Writes search results as CSV (account,term,offset)
+fn export_results(path: &str, terms: &[SearchTerm], results: &[(Pubkey, Vec<Hit>)]) -> Result<()> {
+    let mut file = File::create(path)?;
+    writeln!(file, "account,term,offset")?;
+    for (pubkey, hits) in results {
+        for hit in hits {
+            writeln!(file, "{},\"{}\",{}", pubkey, terms[hit.term].label.replace('"', "\"\""), hit.offset)?;
+        }
+    }
+    Ok(())
+}
+
+/// Searches for one or more patterns across all accounts
+pub fn search_pattern(fetcher: &mut AccountFetcher) -> Result<()> {
+    println!("\n🔍 DATA PATTERN SEARCH");
+    print_syntax();
+    println!("\nEnter search query (e.g., 0102 u64:1000000):");
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    let query = parse_query(input.trim())?;
+    let engine = SearchEngine::new(query)?;
+    let accounts = fetcher.get_all_accounts()?;
+
+    println!("\nSearching {} accounts for {} terms...", accounts.len(), engine.terms.len());
+
+    let mut results: Vec<(Pubkey, Vec<Hit>)> = accounts.iter()
+        .map(|(pubkey, data)| (*pubkey, engine.search(data)))
+        .filter(|(_, hits)| !hits.is_empty())
+        .collect();
+    results.sort_by_key(|(_, hits)| std::cmp::Reverse(hits.len()));
+
+    if results.is_empty() {
+        println!("\n❌ No matches found in any accounts");
+        return Ok(());
+    }
+
+    // Per-term summary
+    let mut term_totals: HashMap<usize, (usize, usize)> = HashMap::new();
+    for (_, hits) in &results {
+        let mut seen = Vec::new();
+        for hit in hits {
+            let entry = term_totals.entry(hit.term).or_default();
+            entry.1 += 1;
+            if !seen.contains(&hit.term) {
+                seen.push(hit.term);
+                entry.0 += 1;
+            }
+        }
+    }
+
+    println!("\n📊 Term summary:");
+    for (i, term) in engine.terms.iter().enumerate() {
+        let (accounts_hit, total) = term_totals.get(&i).copied().unwrap_or_default();
+        println!("  {}: {} hits in {} accounts", term.label, total, accounts_hit);
+    }
+
+    println!("\n📍 Matches per account:");
+    for (pubkey, hits) in &results {
+        println!("\nAccount: {} ({} hits)", pubkey, hits.len());
+        for (i, term) in engine.terms.iter().enumerate() {
+            let offsets: Vec<_> = hits.iter()
+                .filter(|h| h.term == i)
+                .map(|h| h.offset)
+                .collect();
+            if offsets.is_empty() {
+                continue;
+            }
+            let shown: Vec<_> = offsets.iter().take(10).map(|o| o.to_string()).collect();
+            let more = if offsets.len() > 10 {
+                format!(" (+{} more)", offsets.len() - 10)
+            } else {
+                String::new()
+            };
+            println!("  {} x{} at offsets {}{}", term.label, offsets.len(), shown.join(", "), more);
+        }
+    }
+
+    println!("\n✅ Matches found in {} accounts", results.len());
+
+    println!("\nEnter a CSV file path to export results (or press Enter to skip):");
+    let mut path = String::new();
+    io::stdin().read_line(&mut path)?;
+    let path = path.trim();
+    if !path.is_empty() {
+        export_results(path, &engine.terms, &results)?;
+        println!("Results written to {}", path);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(term: &SearchTerm) -> Vec<u8> {
+        match &term.kind {
+            TermKind::Bytes(pattern) => pattern.iter().map(|b| b.unwrap()).collect(),
+            kind => panic!("expected bytes, got {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn string_keeps_at_sign() {
+        let term = parse_term("str:user@example.com").unwrap();
+        assert_eq!(bytes(&term), b"user@example.com");
+        assert_eq!(term.offset, None);
+    }
+
+    #[test]
+    fn quoted_string_with_offset() {
+        let tokens = tokenize(r#"str:"a b@c"@4 u8:1"#).unwrap();
+        assert_eq!(tokens, vec![r#"str:"a b@c"@4"#, "u8:1"]);
+        let term = parse_term(&tokens[0]).unwrap();
+        assert_eq!(bytes(&term), b"a b@c");
+        assert_eq!(term.offset, Some(4));
+        assert!(parse_term(r#"str:"abc"x"#).is_err());
+    }
+
+    #[test]
+    fn integer_with_offset() {
+        let term = parse_term("u64:5@8").unwrap();
+        assert_eq!(bytes(&term), [5, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(term.offset, Some(8));
+        assert_eq!(bytes(&parse_term("i16be:-2").unwrap()), [0xff, 0xfe]);
+        assert!(parse_term("u8:256").is_err());
+        assert!(parse_term("i8:-129").is_err());
+        assert!(parse_term("u8:-1").is_err());
+    }
+
+    #[test]
+    fn bare_hex_wins_over_base58() {
+        let term = parse_term(&"11".repeat(16)).unwrap();
+        assert_eq!(bytes(&term), [0x11; 16]);
+
+        let system = "11111111111111111111111111111111";
+        assert_eq!(bytes(&parse_term(&format!("pk:{}", system)).unwrap()), [0; 32]);
+        let token = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+        assert_eq!(bytes(&parse_term(token).unwrap()), Pubkey::from_str(token).unwrap().to_bytes());
+    }
+
+    #[test]
+    fn u128_range_above_i128_max() {
+        let term = parse_term(&format!("u128:{}..", 1u128 << 127)).unwrap();
+        let engine = SearchEngine::new(SearchQuery { terms: vec![term], discriminator: None }).unwrap();
+
+        let mut data = [0u8; 20];
+        data[4..].copy_from_slice(&(1u128 << 127).to_le_bytes());
+        let offsets: Vec<usize> = engine.search(&data).iter().map(|h| h.offset).collect();
+        assert_eq!(offsets, [4]);
+    }
+
+    #[test]
+    fn signed_range() {
+        let range = IntRange::parse("-10", "-1", 4, true).unwrap();
+        assert!(range.contains(&(-5i32).to_le_bytes(), false));
+        assert!(!range.contains(&5i32.to_le_bytes(), false));
+        assert!(range.contains(&(-1i16).to_be_bytes(), true));
+        assert!(IntRange::parse("5", "1", 8, false).is_err());
+    }
+
+    #[test]
+    fn rejects_range_bounds_outside_the_type() {
+        assert!(parse_term("u8:0..1000").is_err());
+        assert!(parse_term("i16:-40000..0").is_err());
+        assert!(parse_term("u8:-1..").is_err());
+        assert!(parse_term("u8:0..255").is_ok());
+        assert!(parse_term("i16:-32768..").is_ok());
+    }
+
+    #[test]
+    fn search_wildcards_and_anchors() {
+        let query = parse_query("type:0102030405060708 01??03 u8:9@10").unwrap();
+        let engine = SearchEngine::new(query).unwrap();
+
+        let mut data = vec![1, 2, 3, 4, 5, 6, 7, 8, 0, 1, 9, 3];
+        let hits: Vec<(usize, usize)> = engine.search(&data).iter().map(|h| (h.term, h.offset)).collect();
+        assert_eq!(hits, [(0, 0), (0, 9), (1, 10)]);
+
+        data[0] = 0;
+        assert!(engine.search(&data).is_empty());
+    }
+}
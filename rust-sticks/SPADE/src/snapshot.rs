@@ -0,0 +1,105 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+
+use anyhow::{bail, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::AccountFetcher;
+
+/// Magic bytes at the start of every `.spade` file
+const MAGIC: &[u8; 5] = b"SPADE";
+
+/// Bumped whenever the serialized layout changes
+const FORMAT_VERSION: u32 = 1;
+
+/// One account as captured in a snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotAccount {
+    pub pubkey: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+}
+
+/// All accounts of a program at a point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub program_id: Pubkey,
+    pub slot: u64,
+    /// Unix timestamp of when the snapshot was taken
+    pub captured_at: i64,
+    pub accounts: Vec<SnapshotAccount>,
+}
+
+impl Snapshot {
+    /// Fetches every program account and records it with the current slot
+    pub fn capture(fetcher: &mut AccountFetcher) -> Result<Self> {
+        let accounts = fetcher.get_all_accounts_with_meta()?;
+        let (client, program_id) = fetcher.get_client();
+        let slot = client.get_slot()?;
+
+        let mut accounts: Vec<_> = accounts.into_iter()
+            .map(|(pubkey, account)| SnapshotAccount {
+                pubkey,
+                lamports: account.lamports,
+                data: account.data,
+            })
+            .collect();
+        accounts.sort_by_key(|a| a.pubkey);
+
+        Ok(Self {
+            program_id,
+            slot,
+            captured_at: Utc::now().timestamp(),
+            accounts,
+        })
+    }
+
+    /// Loads a `.spade` file, or captures the accounts of the program with that id
+    pub fn from_source(source: &str) -> Result<Self> {
+        if source.ends_with(".spade") {
+            Self::load(source)
+        } else {
+            Self::capture(&mut AccountFetcher::new(source)?)
+        }
+    }
+
+    /// Writes the snapshot to a `.spade` file
+    pub fn save(&self, path: &str) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        bincode::serialize_into(&mut writer, self)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads a snapshot written by [`Snapshot::save`]
+    pub fn load(path: &str) -> Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+
+        let mut magic = [0u8; 5];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            bail!("{} is not a SPADE snapshot", path);
+        }
+
+        let mut version = [0u8; 4];
+        reader.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+        if version != FORMAT_VERSION {
+            bail!("{} has snapshot format v{}, expected v{}", path, version, FORMAT_VERSION);
+        }
+
+        Ok(bincode::deserialize_from(reader)?)
+    }
+}
+
+/// Captures a snapshot of the fetcher's program and saves it to `path`
+pub fn save_snapshot(fetcher: &mut AccountFetcher, path: &str) -> Result<()> {
+    let snapshot = Snapshot::capture(fetcher)?;
+    snapshot.save(path)?;
+    println!("💾 Saved {} accounts at slot {} to {}", snapshot.accounts.len(), snapshot.slot, path);
+    Ok(())
+}
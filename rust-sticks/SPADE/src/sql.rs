@@ -0,0 +1,297 @@
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use rusqlite::types::ValueRef;
+use rusqlite::{params, Connection};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::decoder::DecoderRegistry;
+use crate::filters;
+use crate::layout;
+use crate::snapshot::Snapshot;
+use crate::txs;
+use crate::AccountFetcher;
+
+/// Longest value shown in a result column before it is cut off
+const MAX_COLUMN_WIDTH: usize = 64;
+
+const SCHEMA: &str = "
+CREATE TABLE accounts (
+    pubkey TEXT PRIMARY KEY,
+    owner TEXT NOT NULL,
+    lamports INTEGER NOT NULL,
+    size INTEGER NOT NULL,
+    discriminator TEXT,
+    type_name TEXT NOT NULL,
+    decoder TEXT,
+    data BLOB NOT NULL
+);
+CREATE TABLE fields (
+    pubkey TEXT NOT NULL,
+    offset INTEGER NOT NULL,
+    len INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    value TEXT NOT NULL
+);
+CREATE TABLE refs (
+    pubkey TEXT NOT NULL,
+    offset INTEGER NOT NULL,
+    target TEXT NOT NULL,
+    field TEXT
+);
+CREATE TABLE signatures (
+    address TEXT NOT NULL,
+    signature TEXT PRIMARY KEY,
+    slot INTEGER NOT NULL,
+    block_time INTEGER,
+    err TEXT,
+    memo TEXT
+);
+CREATE INDEX fields_by_name ON fields (name, pubkey);
+CREATE INDEX refs_by_target ON refs (target);
+";
+
+/// Fills the accounts, fields and refs tables from a snapshot
+fn load_snapshot(conn: &Connection, snapshot: &Snapshot, registry: &DecoderRegistry) -> Result<()> {
+    let keys: HashSet<Pubkey> = snapshot.accounts.iter().map(|a| a.pubkey).collect();
+    let owner = snapshot.program_id.to_string();
+
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut insert_account = tx.prepare("INSERT INTO accounts VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)")?;
+        let mut insert_field = tx.prepare("INSERT INTO fields VALUES (?1, ?2, ?3, ?4, ?5)")?;
+        let mut insert_ref = tx.prepare("INSERT INTO refs VALUES (?1, ?2, ?3, ?4)")?;
+
+        for account in &snapshot.accounts {
+            let pubkey = account.pubkey.to_string();
+            let decoded = registry.decode(&account.data, &snapshot.program_id);
+            let discriminator = account.data.get(..8).map(hex::encode);
+            let type_name = match (&decoded, &discriminator) {
+                (Some(decoded), _) => decoded.type_name.clone(),
+                (None, Some(discriminator)) => discriminator.clone(),
+                (None, None) => format!("Unknown ({} bytes)", account.data.len()),
+            };
+            insert_account.execute(params![
+                pubkey,
+                owner,
+                account.lamports as i64,
+                account.data.len() as i64,
+                discriminator,
+                type_name,
+                decoded.as_ref().map(|d| d.decoder.as_str()),
+                account.data,
+            ])?;
+
+            let decoded_fields = decoded.map(|d| d.fields).unwrap_or_default();
+            for field in &decoded_fields {
+                insert_field.execute(params![pubkey, field.offset as i64, field.len as i64, field.name, field.value])?;
+            }
+            // Pubkey fields of the decoded layout, else of the inferred one
+            let fields = if decoded_fields.is_empty() {
+                layout::infer_layout(&account.data, &snapshot.program_id, &keys)
+            } else {
+                decoded_fields
+            };
+            for (field, target) in layout::pubkey_fields(&account.data, &fields) {
+                if target == account.pubkey {
+                    continue;
+                }
+                insert_ref.execute(params![pubkey, field.offset as i64, target.to_string(), field.name])?;
+            }
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Fills the signatures table with the program's most recent signatures
+fn load_signatures(conn: &Connection, program_id: &Pubkey, limit: usize) -> Result<usize> {
+    let mut fetcher = AccountFetcher::new(&program_id.to_string())?;
+    let signatures = txs::fetch_signatures(&mut fetcher, program_id, limit)?;
+
+    let mut insert = conn.prepare("INSERT OR IGNORE INTO signatures VALUES (?1, ?2, ?3, ?4, ?5, ?6)")?;
+    for status in &signatures {
+        insert.execute(params![
+            program_id.to_string(),
+            status.signature,
+            status.slot as i64,
+            status.block_time,
+            status.err.as_ref().map(|e| e.to_string()),
+            status.memo,
+        ])?;
+    }
+    Ok(signatures.len())
+}
+
+fn format_value(value: ValueRef) -> String {
+    let text = match value {
+        ValueRef::Null => "NULL".to_string(),
+        ValueRef::Integer(i) => i.to_string(),
+        ValueRef::Real(f) => f.to_string(),
+        ValueRef::Text(t) => String::from_utf8_lossy(t).to_string(),
+        ValueRef::Blob(b) => format!("x'{}'", hex::encode(b)),
+    };
+    if text.chars().count() > MAX_COLUMN_WIDTH {
+        text.chars().take(MAX_COLUMN_WIDTH - 1).chain(['…']).collect()
+    } else {
+        text
+    }
+}
+
+/// Runs one statement, printing its rows as a table or the number of rows it changed
+fn run_query(conn: &Connection, sql: &str) -> Result<()> {
+    let mut stmt = conn.prepare(sql)?;
+    if stmt.column_count() == 0 {
+        let changed = stmt.execute([])?;
+        println!("{} rows changed", changed);
+        return Ok(());
+    }
+
+    let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+    let mut rows = stmt.query([])?;
+    let mut table: Vec<Vec<String>> = Vec::new();
+    while let Some(row) = rows.next()? {
+        table.push((0..columns.len()).map(|i| row.get_ref(i).map(format_value)).collect::<rusqlite::Result<_>>()?);
+    }
+
+    let widths: Vec<usize> = columns.iter().enumerate()
+        .map(|(i, column)| table.iter().map(|row| row[i].chars().count()).fold(column.chars().count(), usize::max))
+        .collect();
+    let print_row = |cells: &[String]| {
+        let line: Vec<String> = cells.iter().zip(&widths).map(|(cell, width)| format!("{:<width$}", cell, width = width)).collect();
+        println!("{}", line.join(" | ").trim_end());
+    };
+    print_row(&columns);
+    println!("{}", widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>().join("-+-"));
+    for row in &table {
+        print_row(row);
+    }
+    println!("({} rows)", table.len());
+    Ok(())
+}
+
+/// Reads statements ending in `;` until `.quit` or end of input
+fn shell(conn: &Connection) -> Result<()> {
+    println!("\nEnter SQL statements ending with ';', or .tables, .schema, .quit");
+    let mut statement = String::new();
+    loop {
+        print!("{}", if statement.is_empty() { "sql> " } else { "...> " });
+        io::stdout().flush()?;
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line)? == 0 {
+            break;
+        }
+
+        let trimmed = line.trim();
+        if statement.is_empty() {
+            let command = match trimmed {
+                "" => continue,
+                ".quit" | ".exit" => break,
+                ".tables" => Some("SELECT name FROM sqlite_master WHERE type IN ('table', 'view') ORDER BY name"),
+                ".schema" => Some("SELECT sql FROM sqlite_master WHERE sql IS NOT NULL"),
+                _ => None,
+            };
+            if let Some(command) = command {
+                if let Err(e) = run_query(conn, command) {
+                    println!("Error: {}", e);
+                }
+                continue;
+            }
+        }
+
+        statement.push_str(&line);
+        if trimmed.ends_with(';') {
+            if let Err(e) = run_query(conn, statement.trim()) {
+                println!("Error: {}", e);
+            }
+            statement.clear();
+        }
+    }
+    Ok(())
+}
+
+/// Loads a program's accounts (fetched over RPC) or a `.spade` snapshot into SQLite and runs
+/// `query`, or opens a SQL shell without one. A `.db` source is a database saved earlier with
+/// `--db`. Flags: `--idl FILE`, `--limit N` (signatures, 0 to skip; snapshots skip them unless
+/// given, as they are fetched now rather than at the snapshot's slot), `--db FILE`
+pub fn run_sql(source: &str, args: &[String]) -> Result<()> {
+    let query = args.first().filter(|arg| !arg.starts_with("--"));
+
+    let conn = if source.ends_with(".db") {
+        if !Path::new(source).exists() {
+            bail!("{} does not exist", source);
+        }
+        Connection::open(source)?
+    } else {
+        let registry = DecoderRegistry::from_args(args)?;
+        let conn = match filters::flag_values(args, "--db").first() {
+            Some(path) if Path::new(path).exists() => bail!("{} already exists", path),
+            Some(path) => Connection::open(path)?,
+            None => Connection::open_in_memory()?,
+        };
+        conn.execute_batch(SCHEMA)?;
+
+        let snapshot = Snapshot::from_source(source)?;
+        load_snapshot(&conn, &snapshot, &registry)?;
+        println!("🗄️  Loaded {} accounts of {} (slot {})", snapshot.accounts.len(), snapshot.program_id, snapshot.slot);
+
+        let limit = if source.ends_with(".spade") && filters::flag_values(args, "--limit").is_empty() {
+            0
+        } else {
+            txs::limit_from_args(args)?
+        };
+        if limit > 0 {
+            match load_signatures(&conn, &snapshot.program_id, limit) {
+                Ok(count) => println!("🗄️  Loaded {} signatures", count),
+                Err(e) => println!("⚠️  Failed to fetch signatures, the signatures table is empty: {}", e),
+            }
+        }
+        conn
+    };
+
+    match query {
+        Some(query) => run_query(&conn, query),
+        None => shell(&conn),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::SnapshotAccount;
+
+    #[test]
+    fn loads_accounts_fields_and_refs() {
+        let program_id = Pubkey::new_unique();
+        let (pool, vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut pool_data = vec![7u8; 8];
+        pool_data.extend_from_slice(vault.as_ref());
+        pool_data.extend_from_slice(&42u64.to_le_bytes());
+        // An account storing its own key does not reference itself
+        let mut vault_data = vec![0u8; 9];
+        vault_data.extend_from_slice(vault.as_ref());
+        let snapshot = Snapshot {
+            program_id,
+            slot: 1,
+            captured_at: 0,
+            accounts: vec![
+                SnapshotAccount { pubkey: pool, lamports: 10, data: pool_data },
+                SnapshotAccount { pubkey: vault, lamports: 20, data: vault_data },
+            ],
+        };
+
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        load_snapshot(&conn, &snapshot, &DecoderRegistry::with_builtin(None)).unwrap();
+
+        let count: i64 = conn.query_row("SELECT count(*) FROM accounts", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 2);
+        let refs: Vec<(String, i64, String, String)> = conn
+            .prepare("SELECT pubkey, offset, target, field FROM refs").unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))).unwrap()
+            .collect::<rusqlite::Result<_>>().unwrap();
+        assert_eq!(refs, [(pool.to_string(), 8, vault.to_string(), "pubkey".to_string())]);
+    }
+}
@@ -0,0 +1,305 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::AccountFetcher;
+
+/// Longest length prefix accepted as a Borsh string
+const MAX_BORSH_LEN: usize = 1024;
+
+/// How a string was laid out in the account data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StringKind {
+    /// Plain run of printable characters
+    Raw,
+    /// Printable run followed by zero padding, as in fixed `[u8; N]` fields
+    Padded,
+    /// u32 little-endian length prefix followed by UTF-8 bytes
+    Borsh,
+}
+
+impl StringKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StringKind::Raw => "raw",
+            StringKind::Padded => "padded",
+            StringKind::Borsh => "borsh",
+        }
+    }
+}
+
+/// A string found inside account data
+#[derive(Debug, Clone)]
+pub struct FoundString {
+    /// Offset of the first byte (the length prefix for Borsh strings)
+    pub offset: usize,
+    /// Number of bytes the string occupies, including any length prefix
+    pub len: usize,
+    pub kind: StringKind,
+    pub text: String,
+}
+
+/// Decodes one UTF-8 character at `pos`, returning it with its encoded length
+fn char_at(data: &[u8], pos: usize) -> Option<(char, usize)> {
+    let width = match data[pos] {
+        0x00..=0x7f => 1,
+        0xc2..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf4 => 4,
+        _ => return None,
+    };
+    let bytes = data.get(pos..pos + width)?;
+    std::str::from_utf8(bytes).ok()?.chars().next().map(|c| (c, width))
+}
+
+/// True for characters worth reporting as text
+fn is_printable(c: char) -> bool {
+    !c.is_control() || c == '\t'
+}
+
+/// Tries to read a Borsh string (u32 LE length + UTF-8) at `pos`
+fn borsh_string_at(data: &[u8], pos: usize, min_len: usize) -> Option<FoundString> {
+    let prefix = data.get(pos..pos + 4)?;
+    let len = u32::from_le_bytes(prefix.try_into().ok()?) as usize;
+    if len < min_len || len > MAX_BORSH_LEN {
+        return None;
+    }
+
+    let bytes = data.get(pos + 4..pos + 4 + len)?;
+    let text = std::str::from_utf8(bytes).ok()?;
+    // Metaplex-style strings are often padded with NULs inside the declared length
+    let trimmed = text.trim_end_matches('\0');
+    if trimmed.chars().count() < min_len || !trimmed.chars().all(is_printable) {
+        return None;
+    }
+
+    Some(FoundString {
+        offset: pos,
+        len: 4 + len,
+        kind: StringKind::Borsh,
+        text: trimmed.to_string(),
+    })
+}
+
+/// Finds printable text runs and Borsh strings of at least `min_len` characters
+pub fn extract_strings(data: &[u8], min_len: usize) -> Vec<FoundString> {
+    let min_len = min_len.max(1);
+    let mut found = Vec::new();
+
+    // Borsh strings first so raw runs inside them aren't reported twice
+    let mut pos = 0;
+    while pos + 4 <= data.len() {
+        match borsh_string_at(data, pos, min_len) {
+            Some(s) => {
+                pos += s.len;
+                found.push(s);
+            }
+            None => pos += 1,
+        }
+    }
+    // The ranges are sorted and disjoint, and positions only grow, so a cursor walks them once
+    let borsh_ranges: Vec<(usize, usize)> = found.iter()
+        .map(|s| (s.offset, s.offset + s.len))
+        .collect();
+    let mut next_range = 0;
+    let mut inside_borsh = |p: usize| {
+        while borsh_ranges.get(next_range).is_some_and(|&(_, end)| end <= p) {
+            next_range += 1;
+        }
+        borsh_ranges.get(next_range).is_some_and(|&(start, _)| start <= p)
+    };
+
+    let mut pos = 0;
+    while pos < data.len() {
+        if inside_borsh(pos) {
+            pos += 1;
+            continue;
+        }
+
+        let start = pos;
+        let mut text = String::new();
+        while pos < data.len() && !inside_borsh(pos) {
+            match char_at(data, pos) {
+                Some((c, width)) if is_printable(c) => {
+                    text.push(c);
+                    pos += width;
+                }
+                _ => break,
+            }
+        }
+
+        if text.chars().count() >= min_len {
+            let padded = pos < data.len() && data[pos] == 0;
+            found.push(FoundString {
+                offset: start,
+                len: pos - start,
+                kind: if padded { StringKind::Padded } else { StringKind::Raw },
+                text,
+            });
+        }
+
+        if pos == start {
+            pos += 1;
+        }
+    }
+
+    found.sort_by_key(|s| s.offset);
+    found
+}
+
+/// Aggregated statistics for one distinct string across all accounts
+#[derive(Default)]
+struct StringStats {
+    accounts: HashSet<Pubkey>,
+    occurrences: usize,
+    offsets: HashSet<usize>,
+    kinds: HashSet<&'static str>,
+    example: Option<(Pubkey, usize)>,
+}
+
+/// Prints the stats line for one string
+fn print_stats(text: &str, stats: &StringStats) {
+    let mut offsets: Vec<_> = stats.offsets.iter().copied().collect();
+    offsets.sort_unstable();
+    let offsets: Vec<_> = offsets.iter().take(5).map(|o| o.to_string()).collect();
+    let mut kinds: Vec<_> = stats.kinds.iter().copied().collect();
+    kinds.sort_unstable();
+
+    println!("  {:?}", text);
+    println!("     {} accounts, {} occurrences, offsets [{}], {}",
+        stats.accounts.len(), stats.occurrences, offsets.join(", "), kinds.join("/"));
+    if let Some((pubkey, offset)) = stats.example {
+        println!("     e.g. {} @ {}", pubkey, offset);
+    }
+}
+
+/// Extracts embedded strings from all program accounts and reports their frequency
+pub fn analyze_strings(fetcher: &mut AccountFetcher) -> Result<()> {
+    println!("\nEnter minimum string length (default: 4):");
+    let mut len_input = String::new();
+    io::stdin().read_line(&mut len_input)?;
+    let min_len = len_input.trim().parse::<usize>().unwrap_or(4);
+
+    println!("\n🔤 EMBEDDED STRINGS ANALYSIS (>= {} chars)", min_len);
+    println!("====================================");
+
+    let accounts = fetcher.get_all_accounts()?;
+    let mut strings: HashMap<String, StringStats> = HashMap::new();
+    let mut kind_counts: HashMap<StringKind, usize> = HashMap::new();
+
+    for (pubkey, data) in &accounts {
+        for s in extract_strings(data, min_len) {
+            *kind_counts.entry(s.kind).or_default() += 1;
+            let stats = strings.entry(s.text).or_default();
+            stats.accounts.insert(*pubkey);
+            stats.occurrences += 1;
+            stats.offsets.insert(s.offset);
+            stats.kinds.insert(s.kind.as_str());
+            stats.example.get_or_insert((*pubkey, s.offset));
+        }
+    }
+
+    if strings.is_empty() {
+        println!("\nNo strings found in any accounts");
+        return Ok(());
+    }
+
+    println!("Found {} distinct strings in {} accounts", strings.len(), accounts.len());
+    for kind in [StringKind::Borsh, StringKind::Padded, StringKind::Raw] {
+        println!("- {}: {} occurrences", kind.as_str(), kind_counts.get(&kind).unwrap_or(&0));
+    }
+
+    let mut ranked: Vec<_> = strings.iter().collect();
+    ranked.sort_by(|a, b| b.1.accounts.len().cmp(&a.1.accounts.len()).then_with(|| a.0.cmp(b.0)));
+
+    println!("\nHow many strings to display? (default: 25):");
+    let mut limit_input = String::new();
+    io::stdin().read_line(&mut limit_input)?;
+    let limit = limit_input.trim().parse::<usize>().unwrap_or(25);
+
+    println!("\n📊 Most frequent strings:");
+    for (text, stats) in ranked.iter().take(limit) {
+        print_stats(text, stats);
+    }
+
+    loop {
+        println!("\nEnter text to search for (case-insensitive), or press Enter to finish:");
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let needle = input.trim().to_lowercase();
+        if needle.is_empty() {
+            break;
+        }
+
+        let matches: Vec<_> = ranked.iter()
+            .filter(|(text, _)| text.to_lowercase().contains(&needle))
+            .collect();
+        println!("\n{} strings match '{}':", matches.len(), needle);
+        for (text, stats) in matches {
+            print_stats(text, stats);
+            let mut holders: Vec<_> = stats.accounts.iter().collect();
+            holders.sort();
+            for pubkey in holders.iter().take(10) {
+                println!("       - {}", pubkey);
+            }
+            if holders.len() > 10 {
+                println!("       ... and {} more", holders.len() - 10);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn borsh(text: &[u8]) -> Vec<u8> {
+        [&(text.len() as u32).to_le_bytes()[..], text].concat()
+    }
+
+    fn kinds(found: &[FoundString]) -> Vec<(usize, usize, StringKind, &str)> {
+        found.iter().map(|s| (s.offset, s.len, s.kind, s.text.as_str())).collect()
+    }
+
+    #[test]
+    fn classifies_borsh_padded_and_raw() {
+        let mut data = vec![0xff];
+        data.extend(borsh(b"Mad Lads"));
+        data.extend_from_slice(b"\xffMAD\0\0\0\xffhello");
+
+        assert_eq!(kinds(&extract_strings(&data, 3)), [
+            (1, 12, StringKind::Borsh, "Mad Lads"),
+            (14, 3, StringKind::Padded, "MAD"),
+            (21, 5, StringKind::Raw, "hello"),
+        ]);
+    }
+
+    #[test]
+    fn trims_nul_padding_inside_borsh_strings() {
+        let data = borsh(b"https://arweave.net/x\0\0\0\0");
+        let found = borsh_string_at(&data, 0, 3).unwrap();
+        assert_eq!((found.len, found.text.as_str()), (29, "https://arweave.net/x"));
+
+        assert!(borsh_string_at(&borsh(b"ab\0\0"), 0, 3).is_none());
+        assert!(borsh_string_at(&borsh(b"a\x01bc"), 0, 3).is_none());
+        assert!(borsh_string_at(&[0xff, 0xff, 0xff, 0x7f, b'a'], 0, 1).is_none());
+    }
+
+    #[test]
+    fn does_not_report_raw_runs_inside_borsh_strings() {
+        // The text after the prefix would also be a raw run
+        let mut data = borsh(b"first name");
+        data.extend(borsh(b"second"));
+        data.extend_from_slice(b"\x01tail text");
+
+        assert_eq!(kinds(&extract_strings(&data, 4)), [
+            (0, 14, StringKind::Borsh, "first name"),
+            (14, 10, StringKind::Borsh, "second"),
+            (25, 9, StringKind::Raw, "tail text"),
+        ]);
+    }
+}
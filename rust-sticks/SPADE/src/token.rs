@@ -0,0 +1,426 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::labels;
+use crate::layout::{self, Field};
+use crate::AccountFetcher;
+
+pub const TOKEN_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+pub const MINT_LEN: usize = 82;
+pub const ACCOUNT_LEN: usize = 165;
+const MULTISIG_LEN: usize = 355;
+
+/// Accounts fetched per getMultipleAccounts call
+const MULTIPLE_ACCOUNTS_BATCH: usize = 100;
+
+/// Token-2022 extension names, indexed by `ExtensionType`
+const EXTENSION_NAMES: &[&str] = &[
+    "Uninitialized",
+    "TransferFeeConfig",
+    "TransferFeeAmount",
+    "MintCloseAuthority",
+    "ConfidentialTransferMint",
+    "ConfidentialTransferAccount",
+    "DefaultAccountState",
+    "ImmutableOwner",
+    "MemoTransfer",
+    "NonTransferable",
+    "InterestBearingConfig",
+    "CpiGuard",
+    "PermanentDelegate",
+    "NonTransferableAccount",
+    "TransferHook",
+    "TransferHookAccount",
+    "ConfidentialTransferFeeConfig",
+    "ConfidentialTransferFeeAmount",
+    "MetadataPointer",
+    "TokenMetadata",
+    "GroupPointer",
+    "TokenGroup",
+    "GroupMemberPointer",
+    "TokenGroupMember",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Mint,
+    Account,
+    Multisig,
+}
+
+impl TokenKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenKind::Mint => "Mint",
+            TokenKind::Account => "Token Account",
+            TokenKind::Multisig => "Multisig",
+        }
+    }
+}
+
+/// A decoded SPL Token or Token-2022 account
+#[derive(Debug, Clone)]
+pub struct TokenAccount {
+    pub kind: TokenKind,
+    pub fields: Vec<Field>,
+    /// Token-2022 extensions in TLV order
+    pub extensions: Vec<String>,
+}
+
+pub fn is_token_program(program_id: &Pubkey) -> bool {
+    *program_id == TOKEN_PROGRAM_ID || *program_id == TOKEN_2022_PROGRAM_ID
+}
+
+/// Formats a raw token amount with its mint's decimals, e.g. 1500000 with 6 decimals as `1.5`
+pub fn format_amount(amount: u64, decimals: u8) -> String {
+    if decimals == 0 {
+        return amount.to_string();
+    }
+    let digits = format!("{:0>width$}", amount, width = decimals as usize + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+fn describe_key(pubkey: Pubkey) -> String {
+    if pubkey == Pubkey::default() {
+        "none".to_string()
+    } else {
+        labels::describe(&pubkey)
+    }
+}
+
+/// A `COption<Pubkey>`: u32 tag followed by the key
+fn coption_key(data: &[u8], offset: usize, name: &str) -> Option<Field> {
+    let tag = u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?);
+    let key = layout::read_pubkey(data, offset + 4)?;
+    let value = if tag == 0 { "none".to_string() } else { labels::describe(&key) };
+    Some(Field::new(offset, 36, name, value))
+}
+
+/// A plain pubkey, or an `OptionalNonZeroPubkey` where all zeros means unset
+fn key(data: &[u8], offset: usize, name: &str) -> Option<Field> {
+    Some(Field::new(offset, 32, name, describe_key(layout::read_pubkey(data, offset)?)))
+}
+
+fn u64_field(data: &[u8], offset: usize, name: &str) -> Option<Field> {
+    Some(Field::new(offset, 8, name, layout::read_u64(data, offset)?.to_string()))
+}
+
+fn u16_field(data: &[u8], offset: usize, name: &str) -> Option<Field> {
+    let value = u16::from_le_bytes(data.get(offset..offset + 2)?.try_into().ok()?);
+    Some(Field::new(offset, 2, name, value.to_string()))
+}
+
+fn bool_field(data: &[u8], offset: usize, name: &str) -> Option<Field> {
+    Some(Field::new(offset, 1, name, (*data.get(offset)? != 0).to_string()))
+}
+
+fn account_state(state: u8) -> &'static str {
+    match state {
+        0 => "uninitialized",
+        1 => "initialized",
+        2 => "frozen",
+        _ => "unknown",
+    }
+}
+
+fn decode_mint(data: &[u8]) -> Vec<Field> {
+    let decimals = data[44];
+    let supply = layout::read_u64(data, 36).unwrap_or(0);
+    [
+        coption_key(data, 0, "mint_authority"),
+        Some(Field::new(36, 8, "supply", format!("{} ({})", supply, format_amount(supply, decimals)))),
+        Some(Field::new(44, 1, "decimals", decimals.to_string())),
+        bool_field(data, 45, "is_initialized"),
+        coption_key(data, 46, "freeze_authority"),
+    ].into_iter().flatten().collect()
+}
+
+fn decode_account(data: &[u8]) -> Vec<Field> {
+    // COption<u64>: u32 tag followed by the rent-exempt reserve of wrapped SOL accounts
+    let is_native = match data[109..113] {
+        [0, 0, 0, 0] => "no".to_string(),
+        _ => format!("yes, rent-exempt reserve {}", layout::read_u64(data, 113).unwrap_or(0)),
+    };
+    [
+        key(data, 0, "mint"),
+        key(data, 32, "owner"),
+        u64_field(data, 64, "amount"),
+        coption_key(data, 72, "delegate"),
+        Some(Field::new(108, 1, "state", account_state(data[108]))),
+        Some(Field::new(109, 12, "is_native", is_native)),
+        u64_field(data, 121, "delegated_amount"),
+        coption_key(data, 129, "close_authority"),
+    ].into_iter().flatten().collect()
+}
+
+fn decode_multisig(data: &[u8]) -> Vec<Field> {
+    let signers = (data[1] as usize).min(11);
+    let mut fields = vec![
+        Field::new(0, 1, "m", data[0].to_string()),
+        Field::new(1, 1, "n", data[1].to_string()),
+        Field::new(2, 1, "is_initialized", (data[2] != 0).to_string()),
+    ];
+    fields.extend((0..signers).filter_map(|i| key(data, 3 + 32 * i, &format!("signer[{}]", i))));
+    fields
+}
+
+/// Decodes the known fields of a Token-2022 extension whose value starts at `start`
+fn decode_extension(name: &str, data: &[u8], start: usize, len: usize) -> Vec<Field> {
+    let at = |offset: usize| start + offset;
+    let named = |field: &str| format!("{}.{}", name, field);
+
+    let fields = match name {
+        "TransferFeeConfig" => vec![
+            key(data, at(0), &named("transfer_fee_config_authority")),
+            key(data, at(32), &named("withdraw_withheld_authority")),
+            u64_field(data, at(64), &named("withheld_amount")),
+            u64_field(data, at(90), &named("newer_fee_epoch")),
+            u64_field(data, at(98), &named("newer_maximum_fee")),
+            u16_field(data, at(106), &named("newer_fee_basis_points")),
+        ],
+        "TransferFeeAmount" => vec![u64_field(data, at(0), &named("withheld_amount"))],
+        "MintCloseAuthority" => vec![key(data, at(0), &named("close_authority"))],
+        "ConfidentialTransferMint" => vec![
+            key(data, at(0), &named("authority")),
+            bool_field(data, at(32), &named("auto_approve_new_accounts")),
+        ],
+        "ConfidentialTransferAccount" => vec![bool_field(data, at(0), &named("approved"))],
+        "DefaultAccountState" => vec![data.get(at(0)).map(|&state| Field::new(at(0), 1, named("state"), account_state(state)))],
+        "MemoTransfer" => vec![bool_field(data, at(0), &named("require_incoming_transfer_memos"))],
+        "InterestBearingConfig" => vec![
+            key(data, at(0), &named("rate_authority")),
+            data.get(at(50)..at(52)).map(|rate| Field::new(at(50), 2, named("current_rate_bps"),
+                i16::from_le_bytes([rate[0], rate[1]]).to_string())),
+        ],
+        "CpiGuard" => vec![bool_field(data, at(0), &named("lock_cpi"))],
+        "PermanentDelegate" => vec![key(data, at(0), &named("delegate"))],
+        "TransferHook" => vec![
+            key(data, at(0), &named("authority")),
+            key(data, at(32), &named("program_id")),
+        ],
+        "TransferHookAccount" => vec![bool_field(data, at(0), &named("transferring"))],
+        "MetadataPointer" => vec![
+            key(data, at(0), &named("authority")),
+            key(data, at(32), &named("metadata_address")),
+        ],
+        "GroupPointer" => vec![
+            key(data, at(0), &named("authority")),
+            key(data, at(32), &named("group_address")),
+        ],
+        "GroupMemberPointer" => vec![
+            key(data, at(0), &named("authority")),
+            key(data, at(32), &named("member_address")),
+        ],
+        "TokenGroup" => vec![
+            key(data, at(0), &named("update_authority")),
+            key(data, at(32), &named("mint")),
+        ],
+        "TokenGroupMember" => vec![
+            key(data, at(0), &named("mint")),
+            key(data, at(32), &named("group")),
+        ],
+        "TokenMetadata" => {
+            let mut fields = vec![
+                key(data, at(0), &named("update_authority")),
+                key(data, at(32), &named("mint")),
+            ];
+            let mut offset = at(64);
+            for field in ["name", "symbol", "uri"] {
+                let Some((text, text_len)) = layout::read_string(data, offset) else { break };
+                fields.push(Some(Field::new(offset, text_len, named(field), format!("{:?}", text))));
+                offset += text_len;
+            }
+            fields
+        }
+        _ => Vec::new(),
+    };
+    fields.into_iter().flatten().filter(|f| f.end() <= start + len).collect()
+}
+
+/// Walks the Token-2022 TLV extensions that follow the account type byte
+fn decode_extensions(data: &[u8], fields: &mut Vec<Field>) -> Vec<String> {
+    let mut extensions = Vec::new();
+    let mut offset = ACCOUNT_LEN + 1;
+    while offset + 4 <= data.len() {
+        let extension_type = u16::from_le_bytes([data[offset], data[offset + 1]]) as usize;
+        let len = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        if extension_type == 0 || offset + 4 + len > data.len() {
+            break;
+        }
+        let name = EXTENSION_NAMES.get(extension_type)
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| format!("Extension({})", extension_type));
+        fields.push(Field::new(offset, 4, "extension", format!("{} ({} bytes)", name, len)));
+        fields.extend(decode_extension(&name, data, offset + 4, len));
+        extensions.push(name);
+        offset += 4 + len;
+    }
+    extensions
+}
+
+/// Decodes a Token or Token-2022 account. There is no discriminator: the base layouts are
+/// told apart by size, and Token-2022 accounts with extensions by the account type byte
+/// written right after the 165-byte account layout
+pub fn decode(data: &[u8]) -> Option<TokenAccount> {
+    let kind = match data.len() {
+        MINT_LEN => TokenKind::Mint,
+        ACCOUNT_LEN => TokenKind::Account,
+        MULTISIG_LEN => TokenKind::Multisig,
+        len if len > ACCOUNT_LEN => match data[ACCOUNT_LEN] {
+            1 => TokenKind::Mint,
+            2 => TokenKind::Account,
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    let mut fields = match kind {
+        TokenKind::Mint => decode_mint(data),
+        TokenKind::Account => decode_account(data),
+        TokenKind::Multisig => decode_multisig(data),
+    };
+    let mut extensions = Vec::new();
+    if data.len() > ACCOUNT_LEN && kind != TokenKind::Multisig {
+        fields.push(Field::new(ACCOUNT_LEN, 1, "account_type", kind.as_str()));
+        extensions = decode_extensions(data, &mut fields);
+    }
+
+    Some(TokenAccount { kind, fields, extensions })
+}
+
+impl TokenAccount {
+    /// Type label used to group accounts, e.g. `Mint [TransferFeeConfig, MetadataPointer]`
+    pub fn label(&self) -> String {
+        if self.extensions.is_empty() {
+            self.kind.as_str().to_string()
+        } else {
+            format!("{} [{}]", self.kind.as_str(), self.extensions.join(", "))
+        }
+    }
+
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.iter().find(|f| f.name == name).map(|f| f.value.as_str())
+    }
+
+    /// One-line summary shown for referenced token accounts
+    pub fn summary(&self) -> String {
+        let get = |name| self.field(name).unwrap_or("?");
+        match self.kind {
+            TokenKind::Mint => format!("Mint, supply {}, {} decimals", get("supply"), get("decimals")),
+            TokenKind::Account => format!("Token Account, {} of mint {} owned by {}", get("amount"), get("mint"), get("owner")),
+            TokenKind::Multisig => format!("Multisig, {} of {}", get("m"), get("n")),
+        }
+    }
+}
+
+/// Finds the pubkey fields of an account's layout (decoded or inferred) that are Token or
+/// Token-2022 accounts, returned with their offset. `known` accounts (such as the program's
+/// own) are skipped, and nothing is fetched when no field holds a pubkey
+pub fn find_token_references(
+    fetcher: &mut AccountFetcher,
+    data: &[u8],
+    fields: &[Field],
+    known: &HashSet<Pubkey>,
+) -> Result<Vec<(usize, Pubkey, TokenAccount)>> {
+    let mut seen = HashSet::new();
+    let candidates: Vec<(usize, Pubkey)> = layout::pubkey_fields(data, fields).into_iter()
+        .filter(|(_, pubkey)| !known.contains(pubkey) && seen.insert(*pubkey))
+        .map(|(field, pubkey)| (field.offset, pubkey))
+        .collect();
+
+    let mut references = Vec::new();
+    for batch in candidates.chunks(MULTIPLE_ACCOUNTS_BATCH) {
+        let keys: Vec<Pubkey> = batch.iter().map(|(_, pubkey)| *pubkey).collect();
+        let accounts = fetcher.with_retry(|client| Ok(client.get_multiple_accounts(&keys)?))?;
+        for ((offset, pubkey), account) in batch.iter().zip(accounts) {
+            let Some(account) = account.filter(|a| is_token_program(&a.owner)) else { continue };
+            if let Some(token) = decode(&account.data) {
+                references.push((*offset, *pubkey, token));
+            }
+        }
+    }
+    Ok(references)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mint(decimals: u8, supply: u64) -> Vec<u8> {
+        let mut data = vec![0u8; MINT_LEN];
+        data[36..44].copy_from_slice(&supply.to_le_bytes());
+        data[44] = decimals;
+        data[45] = 1;
+        data
+    }
+
+    fn tlv(data: &mut Vec<u8>, extension_type: u16, value: &[u8]) {
+        data.extend_from_slice(&extension_type.to_le_bytes());
+        data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+        data.extend_from_slice(value);
+    }
+
+    #[test]
+    fn formats_amounts() {
+        assert_eq!(format_amount(1_500_000, 6), "1.5");
+        assert_eq!(format_amount(5, 3), "0.005");
+        assert_eq!(format_amount(2_000, 3), "2");
+        assert_eq!(format_amount(42, 0), "42");
+    }
+
+    #[test]
+    fn decodes_base_mint() {
+        let token = decode(&mint(6, 1_500_000)).unwrap();
+        assert_eq!(token.label(), "Mint");
+        assert_eq!(token.field("supply"), Some("1500000 (1.5)"));
+        assert_eq!(token.field("mint_authority"), Some("none"));
+        assert!(decode(&[0; 100]).is_none());
+    }
+
+    #[test]
+    fn walks_token_2022_extensions() {
+        let close_authority = Pubkey::new_unique();
+        let metadata = Pubkey::new_unique();
+
+        let mut data = mint(9, 0);
+        data.resize(ACCOUNT_LEN, 0);
+        data.push(1);
+        tlv(&mut data, 3, close_authority.as_ref());
+        tlv(&mut data, 18, &[[0; 32], metadata.to_bytes()].concat());
+        // Unknown extension types are listed but not decoded
+        tlv(&mut data, 99, &[1, 2]);
+        data.extend_from_slice(&[0; 8]);
+
+        let token = decode(&data).unwrap();
+        assert_eq!(token.kind, TokenKind::Mint);
+        assert_eq!(token.label(), "Mint [MintCloseAuthority, MetadataPointer, Extension(99)]");
+        assert_eq!(token.field("MintCloseAuthority.close_authority"), Some(close_authority.to_string().as_str()));
+        assert_eq!(token.field("MetadataPointer.authority"), Some("none"));
+        assert_eq!(token.field("MetadataPointer.metadata_address"), Some(metadata.to_string().as_str()));
+
+        let offsets: Vec<usize> = layout::pubkey_fields(&data, &token.fields).iter().map(|(f, _)| f.offset).collect();
+        assert_eq!(offsets, [ACCOUNT_LEN + 5, ACCOUNT_LEN + 5 + 32 + 4 + 32]);
+    }
+
+    #[test]
+    fn truncated_extension_stops_the_walk() {
+        let mut data = mint(0, 0);
+        data.resize(ACCOUNT_LEN, 0);
+        data.push(2);
+        tlv(&mut data, 7, &[]);
+        data.extend_from_slice(&[8, 0, 10, 0, 1]);
+        let token = decode(&data).unwrap();
+        assert_eq!(token.kind, TokenKind::Account);
+        assert_eq!(token.extensions, ["ImmutableOwner"]);
+    }
+}
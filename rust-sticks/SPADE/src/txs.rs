@@ -0,0 +1,481 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::TransactionError;
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, UiInstruction, UiTransactionEncoding,
+};
+
+use crate::filters;
+use crate::idl::{self, Idl};
+use crate::AccountFetcher;
+
+/// Signatures requested per getSignaturesForAddress page (RPC maximum)
+const SIGNATURE_PAGE_SIZE: usize = 1000;
+
+/// Default number of transactions analyzed
+const DEFAULT_TX_LIMIT: usize = 200;
+
+/// One instruction of a transaction
+#[derive(Debug, Clone)]
+pub struct Ix {
+    pub program_id: Pubkey,
+    pub data: Vec<u8>,
+    /// Indices into the transaction's `account_keys`
+    pub accounts: Vec<usize>,
+    /// 1 for top-level instructions, 2+ for CPIs
+    pub stack_height: u32,
+}
+
+/// The parts of a fetched transaction the transaction analyses use
+#[derive(Debug, Clone)]
+pub struct ProgramTx {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub error: Option<TransactionError>,
+    pub fee: u64,
+    /// Compute units consumed by the whole transaction, if the node reports it
+    pub compute_units: Option<u64>,
+    /// Static keys followed by writable then readonly lookup table addresses
+    pub account_keys: Vec<Pubkey>,
+    /// Whether each of `account_keys` was write-locked
+    pub writable: Vec<bool>,
+    pub num_signers: usize,
+    pub instructions: Vec<Ix>,
+    /// Inner instructions keyed by the index of the top-level instruction that produced them
+    pub inner_instructions: Vec<(usize, Vec<Ix>)>,
+    pub logs: Vec<String>,
+}
+
+impl ProgramTx {
+    pub fn success(&self) -> bool {
+        self.error.is_none()
+    }
+
+    pub fn fee_payer(&self) -> Option<&Pubkey> {
+        self.account_keys.first()
+    }
+
+    pub fn signers(&self) -> &[Pubkey] {
+        &self.account_keys[..self.num_signers.min(self.account_keys.len())]
+    }
+
+    /// Keys the transaction write-locked
+    pub fn writable_keys(&self) -> impl Iterator<Item = &Pubkey> {
+        self.account_keys.iter().zip(&self.writable)
+            .filter(|(_, writable)| **writable)
+            .map(|(key, _)| key)
+    }
+
+    /// Every instruction that invokes `program_id`, top-level or via CPI,
+    /// with the index of the top-level instruction it belongs to
+    pub fn invocations<'a>(&'a self, program_id: &'a Pubkey) -> impl Iterator<Item = (usize, &'a Ix)> + 'a {
+        let outer = self.instructions.iter().enumerate();
+        let inner = self.inner_instructions.iter()
+            .flat_map(|(index, ixs)| ixs.iter().map(move |ix| (*index, ix)));
+        outer.chain(inner).filter(move |(_, ix)| ix.program_id == *program_id)
+    }
+
+    /// Converts an RPC transaction into a `ProgramTx`, returning None for undecodable transactions
+    pub fn from_encoded(signature: &str, tx: EncodedConfirmedTransactionWithStatusMeta) -> Option<Self> {
+        let versioned = tx.transaction.transaction.decode()?;
+        let meta = tx.transaction.meta?;
+        let message = &versioned.message;
+        let num_signed = message.header().num_required_signatures as usize;
+        let mut account_keys = message.static_account_keys().to_vec();
+        let mut writable: Vec<bool> = (0..account_keys.len())
+            .map(|i| message.is_maybe_writable(i))
+            .collect();
+
+        let loaded: Option<_> = meta.loaded_addresses.into();
+        if let Some(loaded) = loaded {
+            let loaded: solana_transaction_status::UiLoadedAddresses = loaded;
+            for key in loaded.writable.iter().chain(&loaded.readonly) {
+                account_keys.push(Pubkey::from_str(key).ok()?);
+            }
+            writable.extend(loaded.writable.iter().map(|_| true));
+            writable.extend(loaded.readonly.iter().map(|_| false));
+        }
+
+        let key_at = |index: usize| account_keys.get(index).copied();
+
+        let instructions = message.instructions().iter()
+            .map(|ix| Some(Ix {
+                program_id: key_at(ix.program_id_index as usize)?,
+                data: ix.data.clone(),
+                accounts: ix.accounts.iter().map(|&a| a as usize).collect(),
+                stack_height: 1,
+            }))
+            .collect::<Option<Vec<_>>>()?;
+
+        let inner: Option<Vec<_>> = meta.inner_instructions.into();
+        let inner_instructions = inner.unwrap_or_default().into_iter()
+            .map(|inner| {
+                let ixs = inner.instructions.into_iter()
+                    .filter_map(|ix| match ix {
+                        UiInstruction::Compiled(ix) => Some(Ix {
+                            program_id: key_at(ix.program_id_index as usize)?,
+                            data: bs58::decode(&ix.data).into_vec().ok()?,
+                            accounts: ix.accounts.iter().map(|&a| a as usize).collect(),
+                            stack_height: ix.stack_height.unwrap_or(2),
+                        }),
+                        UiInstruction::Parsed(_) => None,
+                    })
+                    .collect();
+                (inner.index as usize, ixs)
+            })
+            .collect();
+
+        let logs: Option<Vec<String>> = meta.log_messages.into();
+        let compute_units: Option<u64> = meta.compute_units_consumed.into();
+
+        Some(Self {
+            signature: signature.to_string(),
+            slot: tx.slot,
+            block_time: tx.block_time,
+            error: meta.err,
+            fee: meta.fee,
+            compute_units,
+            account_keys,
+            writable,
+            num_signers: num_signed,
+            instructions,
+            inner_instructions,
+            logs: logs.unwrap_or_default(),
+        })
+    }
+}
+
+/// Returns `--limit N` from the arguments, or the default
+pub fn limit_from_args(args: &[String]) -> Result<usize> {
+    Ok(filters::flag_values(args, "--limit")
+        .first()
+        .map(|l| l.parse::<usize>())
+        .transpose()?
+        .unwrap_or(DEFAULT_TX_LIMIT))
+}
+
+/// Pages backwards through the signatures for `address`, newest first, up to `limit`
+pub fn fetch_signatures(
+    fetcher: &mut AccountFetcher,
+    address: &Pubkey,
+    limit: usize,
+) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+    let mut signatures = Vec::new();
+    let mut before: Option<Signature> = None;
+
+    while signatures.len() < limit {
+        let page_size = (limit - signatures.len()).min(SIGNATURE_PAGE_SIZE);
+        let page = fetcher.with_retry(|client| {
+            Ok(client.get_signatures_for_address_with_config(address, GetConfirmedSignaturesForAddress2Config {
+                before,
+                until: None,
+                limit: Some(page_size),
+                commitment: None,
+            })?)
+        })?;
+
+        let Some(last) = page.last() else { break };
+        before = Some(Signature::from_str(&last.signature)?);
+        let done = page.len() < page_size;
+        signatures.extend(page);
+        println!("Fetched {} signatures...", signatures.len());
+        if done {
+            break;
+        }
+    }
+
+    Ok(signatures)
+}
+
+/// Fetches up to `limit` of the most recent transactions mentioning `address`
+pub fn fetch_transactions(fetcher: &mut AccountFetcher, address: &Pubkey, limit: usize) -> Result<Vec<ProgramTx>> {
+    let signatures = fetch_signatures(fetcher, address, limit)?;
+    println!("Fetching {} transactions (this may take a while)...", signatures.len());
+
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: None,
+        max_supported_transaction_version: Some(0),
+    };
+
+    let mut txs = Vec::with_capacity(signatures.len());
+    for (i, status) in signatures.iter().enumerate() {
+        let signature = Signature::from_str(&status.signature)?;
+        match fetcher.with_retry(|client| Ok(client.get_transaction_with_config(&signature, config)?)) {
+            Ok(tx) => match ProgramTx::from_encoded(&status.signature, tx) {
+                Some(tx) => txs.push(tx),
+                None => println!("Skipping undecodable transaction {}", status.signature),
+            },
+            Err(e) => println!("Failed to fetch {}: {}", status.signature, e),
+        }
+
+        if (i + 1) % 50 == 0 {
+            println!("Fetched {}/{} transactions...", i + 1, signatures.len());
+        }
+
+        // Add a small delay to avoid rate limiting
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    Ok(txs)
+}
+
+/// Labels instruction data by IDL name, or by its discriminator bytes
+pub fn instruction_label(idl: Option<&Idl>, data: &[u8]) -> String {
+    if let Some(name) = idl.and_then(|idl| idl.instruction_name(data)) {
+        return name.to_string();
+    }
+    match data.len() {
+        0 => "(no data)".to_string(),
+        1..=7 => format!("tag {}", data[0]),
+        _ => hex::encode(&data[..8]),
+    }
+}
+
+/// Formats a unix timestamp as a date
+pub fn format_day(timestamp: i64) -> String {
+    DateTime::<Utc>::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+/// Per-instruction-type counters
+#[derive(Default)]
+struct InstructionStats {
+    total: usize,
+    failed: usize,
+    via_cpi: usize,
+}
+
+/// Pages the program's transactions and reports instruction mix, signers,
+/// success rate and activity over time
+pub fn analyze_transactions(fetcher: &mut AccountFetcher, args: &[String]) -> Result<()> {
+    let limit = limit_from_args(args)?;
+    let idl = idl::from_args(args)?;
+    let program_id = fetcher.program_id;
+
+    println!("\n📜 TRANSACTION HISTORY ANALYSIS");
+    println!("==============================");
+
+    let txs = fetch_transactions(fetcher, &program_id, limit)?;
+    if txs.is_empty() {
+        println!("\nNo transactions found");
+        return Ok(());
+    }
+
+    let failed = txs.iter().filter(|tx| !tx.success()).count();
+    let times: Vec<i64> = txs.iter().filter_map(|tx| tx.block_time).collect();
+    println!("\nAnalyzed {} transactions", txs.len());
+    if let (Some(first), Some(last)) = (times.iter().min(), times.iter().max()) {
+        println!("Time span: {} → {}", format_day(*first), format_day(*last));
+    }
+    if let (Some(first), Some(last)) = (txs.iter().map(|tx| tx.slot).min(), txs.iter().map(|tx| tx.slot).max()) {
+        println!("Slot span: {} → {}", first, last);
+    }
+    println!("Fees paid: {} lamports", txs.iter().map(|tx| tx.fee).sum::<u64>());
+    println!("Succeeded: {} ({:.1}%)", txs.len() - failed, 100.0 * (txs.len() - failed) as f64 / txs.len() as f64);
+    println!("Failed:    {} ({:.1}%)", failed, 100.0 * failed as f64 / txs.len() as f64);
+
+    let recent_failures: Vec<_> = txs.iter().filter(|tx| !tx.success()).take(5).collect();
+    if !recent_failures.is_empty() {
+        println!("Most recent failures:");
+        for tx in recent_failures {
+            println!("  {} ({})", tx.signature, tx.error.as_ref().map(|e| e.to_string()).unwrap_or_default());
+        }
+    }
+
+    // Instruction mix
+    let mut mix: HashMap<String, InstructionStats> = HashMap::new();
+    for tx in &txs {
+        for (_, ix) in tx.invocations(&program_id) {
+            let stats = mix.entry(instruction_label(idl.as_ref(), &ix.data)).or_default();
+            stats.total += 1;
+            if !tx.success() {
+                stats.failed += 1;
+            }
+            if ix.stack_height > 1 {
+                stats.via_cpi += 1;
+            }
+        }
+    }
+    let total_ixs: usize = mix.values().map(|s| s.total).sum();
+    let mut mix: Vec<_> = mix.into_iter().collect();
+    mix.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.total));
+
+    println!("\n🧩 Instruction mix ({} invocations):", total_ixs);
+    for (label, stats) in &mix {
+        println!("  {:<40} {:>6} ({:>5.1}%)  failed {:>5.1}%  via CPI {}",
+            label, stats.total,
+            100.0 * stats.total as f64 / total_ixs.max(1) as f64,
+            100.0 * stats.failed as f64 / stats.total as f64,
+            stats.via_cpi);
+    }
+
+    // Signers and fee payers
+    let mut fee_payers: HashMap<Pubkey, usize> = HashMap::new();
+    let mut signers: HashSet<Pubkey> = HashSet::new();
+    for tx in &txs {
+        if let Some(payer) = tx.fee_payer() {
+            *fee_payers.entry(*payer).or_default() += 1;
+        }
+        signers.extend(tx.signers());
+    }
+    let mut top_payers: Vec<_> = fee_payers.iter().collect();
+    top_payers.sort_by_key(|(_, count)| std::cmp::Reverse(**count));
+
+    println!("\n👥 Signers:");
+    println!("Unique signers:    {}", signers.len());
+    println!("Unique fee payers: {}", fee_payers.len());
+    println!("Top fee payers:");
+    for (payer, count) in top_payers.iter().take(10) {
+        println!("  {} ({} txs)", payer, count);
+    }
+
+    // Volume over time
+    let mut per_day: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    for tx in &txs {
+        let day = tx.block_time.map(format_day).unwrap_or_else(|| "unknown".to_string());
+        let entry = per_day.entry(day).or_default();
+        entry.0 += 1;
+        if !tx.success() {
+            entry.1 += 1;
+        }
+    }
+
+    println!("\n📅 Volume over time:");
+    let max = per_day.values().map(|(total, _)| *total).max().unwrap_or(1);
+    for (day, (total, failed)) in &per_day {
+        let bar = "█".repeat((total * 40).div_ceil(max));
+        println!("  {} {:>6} txs ({:>4} failed) {}", day, total, failed, bar);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use serde_json::json;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::instruction::{CompiledInstruction, InstructionError};
+    use solana_sdk::message::v0::{self, LoadedAddresses, MessageAddressTableLookup};
+    use solana_sdk::message::{MessageHeader, VersionedMessage};
+    use solana_sdk::transaction::VersionedTransaction;
+    use solana_transaction_status::{
+        EncodedTransaction, EncodedTransactionWithStatusMeta, InnerInstruction, InnerInstructions,
+        TransactionStatusMeta,
+    };
+
+    /// A transaction over `account_keys` (all writable) with the given instructions, for the
+    /// analyses built on `ProgramTx`
+    pub fn program_tx(account_keys: Vec<Pubkey>, instructions: Vec<Ix>, inner_instructions: Vec<(usize, Vec<Ix>)>) -> ProgramTx {
+        ProgramTx {
+            signature: "sig".to_string(),
+            slot: 1,
+            block_time: None,
+            error: None,
+            fee: 5000,
+            compute_units: None,
+            writable: vec![true; account_keys.len()],
+            account_keys,
+            num_signers: 1,
+            instructions,
+            inner_instructions,
+            logs: Vec::new(),
+        }
+    }
+
+    pub fn ix(program_id: Pubkey, data: &[u8], accounts: &[usize], stack_height: u32) -> Ix {
+        Ix { program_id, data: data.to_vec(), accounts: accounts.to_vec(), stack_height }
+    }
+
+    #[test]
+    fn converts_versioned_transactions() {
+        let (payer, state, program) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let (loaded_writable, loaded_readonly) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let message = VersionedMessage::V0(v0::Message {
+            header: MessageHeader { num_required_signatures: 1, num_readonly_signed_accounts: 0, num_readonly_unsigned_accounts: 1 },
+            account_keys: vec![payer, state, program],
+            recent_blockhash: Hash::default(),
+            instructions: vec![CompiledInstruction::new_from_raw_parts(2, vec![1, 2, 3], vec![0, 1, 3, 4])],
+            address_table_lookups: vec![MessageAddressTableLookup {
+                account_key: Pubkey::new_unique(),
+                writable_indexes: vec![0],
+                readonly_indexes: vec![1],
+            }],
+        });
+        let tx = VersionedTransaction { signatures: vec![Default::default()], message };
+        let meta = TransactionStatusMeta {
+            status: Err(TransactionError::InstructionError(0, InstructionError::Custom(6000))),
+            fee: 5000,
+            inner_instructions: Some(vec![InnerInstructions {
+                index: 0,
+                instructions: vec![InnerInstruction {
+                    instruction: CompiledInstruction::new_from_raw_parts(4, vec![9], vec![3]),
+                    stack_height: Some(2),
+                }],
+            }]),
+            log_messages: Some(vec!["Program log: hi".to_string()]),
+            loaded_addresses: LoadedAddresses { writable: vec![loaded_writable], readonly: vec![loaded_readonly] },
+            compute_units_consumed: Some(1234),
+            ..Default::default()
+        };
+        let encoded = EncodedConfirmedTransactionWithStatusMeta {
+            slot: 7,
+            block_time: Some(1_700_000_000),
+            transaction: EncodedTransactionWithStatusMeta {
+                transaction: EncodedTransaction::LegacyBinary(bs58::encode(bincode::serialize(&tx).unwrap()).into_string()),
+                meta: Some(meta.into()),
+                version: None,
+            },
+        };
+
+        let tx = ProgramTx::from_encoded("sig", encoded).unwrap();
+        assert_eq!(tx.account_keys, [payer, state, program, loaded_writable, loaded_readonly]);
+        assert_eq!(tx.writable, [true, true, false, true, false]);
+        assert_eq!(tx.signers(), [payer]);
+        assert_eq!((tx.slot, tx.fee, tx.compute_units, tx.success()), (7, 5000, Some(1234), false));
+        assert_eq!(tx.logs, ["Program log: hi"]);
+        assert_eq!(tx.instructions[0].accounts, [0, 1, 3, 4]);
+        assert_eq!(tx.instructions[0].data, [1, 2, 3]);
+
+        let (index, inner) = &tx.inner_instructions[0];
+        assert_eq!((*index, inner[0].program_id, inner[0].stack_height), (0, loaded_readonly, 2));
+        assert_eq!(tx.invocations(&loaded_readonly).count(), 1);
+        assert_eq!(tx.writable_keys().collect::<Vec<_>>(), [&payer, &state, &loaded_writable]);
+    }
+
+    #[test]
+    fn finds_top_level_and_cpi_invocations() {
+        let (program, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let tx = program_tx(
+            vec![Pubkey::new_unique(), program, other],
+            vec![ix(other, &[], &[0], 1), ix(program, &[1], &[0], 1)],
+            vec![(0, vec![ix(program, &[2], &[0], 2)])],
+        );
+        let found: Vec<_> = tx.invocations(&program).map(|(index, ix)| (index, ix.data[0])).collect();
+        assert_eq!(found, [(1, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn labels_instructions() {
+        let idl = Idl::parse(&json!({ "instructions": [{ "name": "deposit" }] })).unwrap();
+        let deposit = idl::anchor_discriminator("global", "deposit");
+        assert_eq!(instruction_label(Some(&idl), &[&deposit[..], &[1, 2]].concat()), "deposit");
+        assert_eq!(instruction_label(Some(&idl), &[1; 9]), "0101010101010101");
+        assert_eq!(instruction_label(None, &[3, 0, 0, 0]), "tag 3");
+        assert_eq!(instruction_label(None, &[]), "(no data)");
+    }
+}
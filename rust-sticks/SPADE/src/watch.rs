@@ -0,0 +1,397 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+use solana_account_decoder::{UiAccount, UiAccountEncoding};
+use solana_client::pubsub_client::PubsubClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::RpcFilterType;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::decoder::DecoderRegistry;
+use crate::diff;
+use crate::filters;
+use crate::snapshot::SnapshotAccount;
+use crate::AccountFetcher;
+
+/// Delay before re-subscribing after a WebSocket disconnect
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// An account notification from any of the subscriptions
+pub struct AccountUpdate {
+    pub slot: u64,
+    pub pubkey: Pubkey,
+    pub account: Account,
+    /// From an `--account` subscription rather than the program subscription
+    pub watched: bool,
+}
+
+/// What an update did to the in-memory snapshot
+pub enum Change {
+    Created(SnapshotAccount),
+    Updated(SnapshotAccount, SnapshotAccount),
+    Closed(SnapshotAccount),
+}
+
+/// In-memory copy of the program's accounts plus the `--account` ones, kept current from
+/// notifications
+pub struct LiveSnapshot {
+    pub program_id: Pubkey,
+    pub accounts: HashMap<Pubkey, SnapshotAccount>,
+    filters: Vec<RpcFilterType>,
+    /// Accounts watched with `--account`, whatever their owner
+    watched: HashSet<Pubkey>,
+    /// Owners of accounts not owned by the program
+    owners: HashMap<Pubkey, Pubkey>,
+}
+
+impl LiveSnapshot {
+    /// Loads the initial account set with the same filters used for the subscription,
+    /// plus the watched accounts that exist
+    pub fn load(fetcher: &mut AccountFetcher, filters: Vec<RpcFilterType>, watched: &[Pubkey]) -> Result<Self> {
+        let mut snapshot = Self {
+            program_id: fetcher.program_id,
+            accounts: HashMap::new(),
+            filters: filters.clone(),
+            watched: watched.iter().copied().collect(),
+            owners: HashMap::new(),
+        };
+
+        for (pubkey, account) in fetcher.get_filtered_accounts(filters)? {
+            snapshot.insert(pubkey, &account);
+        }
+        if !watched.is_empty() {
+            let accounts = fetcher.with_retry(|client| Ok(client.get_multiple_accounts(watched)?))?;
+            for (pubkey, account) in watched.iter().zip(accounts) {
+                if let Some(account) = account {
+                    snapshot.insert(*pubkey, &account);
+                }
+            }
+        }
+        Ok(snapshot)
+    }
+
+    fn insert(&mut self, pubkey: Pubkey, account: &Account) -> Option<SnapshotAccount> {
+        if account.owner == self.program_id {
+            self.owners.remove(&pubkey);
+        } else {
+            self.owners.insert(pubkey, account.owner);
+        }
+        self.accounts.insert(pubkey, SnapshotAccount { pubkey, lamports: account.lamports, data: account.data.clone() })
+    }
+
+    fn remove(&mut self, pubkey: &Pubkey) -> Option<SnapshotAccount> {
+        self.owners.remove(pubkey);
+        self.accounts.remove(pubkey)
+    }
+
+    /// Owner of a tracked account, the program unless it is a watched account of another program
+    pub fn owner_of(&self, pubkey: &Pubkey) -> Pubkey {
+        self.owners.get(pubkey).copied().unwrap_or(self.program_id)
+    }
+
+    /// Applies a notification, returning the change if it altered the snapshot
+    pub fn apply(&mut self, update: AccountUpdate) -> Option<Change> {
+        // Closing an account drains its lamports and hands it back to the System Program. A
+        // watched account may belong to any program, so only lamports tell it was closed
+        let watched = update.watched || self.watched.contains(&update.pubkey);
+        let closed = update.account.lamports == 0 || (!watched && update.account.owner != self.program_id);
+        if closed {
+            return self.remove(&update.pubkey).map(Change::Closed);
+        }
+
+        let new = SnapshotAccount {
+            pubkey: update.pubkey,
+            lamports: update.account.lamports,
+            data: update.account.data.clone(),
+        };
+
+        match self.accounts.get(&update.pubkey) {
+            Some(old) if old.lamports == new.lamports && old.data == new.data => None,
+            Some(_) => {
+                let old = self.insert(update.pubkey, &update.account)?;
+                Some(Change::Updated(old, new))
+            }
+            None => {
+                if !watched && !filters::matches(&self.filters, &new.data) {
+                    println!("   (new account {} does not match the filters, not tracked)", update.pubkey);
+                    return None;
+                }
+                self.insert(update.pubkey, &update.account);
+                Some(Change::Created(new))
+            }
+        }
+    }
+}
+
+/// Account config shared by all subscriptions
+fn account_config() -> RpcAccountInfoConfig {
+    RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        data_slice: None,
+        commitment: None,
+        min_context_slot: None,
+    }
+}
+
+/// Decodes a notification payload into an account update
+fn decode_update(slot: u64, pubkey: Pubkey, account: &UiAccount, watched: bool) -> Option<AccountUpdate> {
+    account.decode::<Account>().map(|account| AccountUpdate { slot, pubkey, account, watched })
+}
+
+/// Keeps a programSubscribe subscription alive, forwarding updates until the receiver is dropped
+pub fn spawn_program_subscription(
+    ws_url: String,
+    program_id: Pubkey,
+    filters: Vec<RpcFilterType>,
+    sender: Sender<AccountUpdate>,
+) {
+    thread::spawn(move || loop {
+        let config = RpcProgramAccountsConfig {
+            filters: if filters.is_empty() { None } else { Some(filters.clone()) },
+            account_config: account_config(),
+            with_context: Some(true),
+        };
+
+        match PubsubClient::program_subscribe(&ws_url, &program_id, Some(config)) {
+            Ok((_subscription, receiver)) => {
+                for response in receiver.iter() {
+                    let Ok(pubkey) = Pubkey::from_str(&response.value.pubkey) else { continue };
+                    if let Some(update) = decode_update(response.context.slot, pubkey, &response.value.account, false) {
+                        if sender.send(update).is_err() {
+                            return;
+                        }
+                    }
+                }
+                println!("⚠️  Program subscription disconnected, reconnecting...");
+            }
+            Err(e) => println!("⚠️  programSubscribe failed: {}, retrying...", e),
+        }
+        thread::sleep(RECONNECT_DELAY);
+    });
+}
+
+/// Keeps an accountSubscribe subscription alive, forwarding updates until the receiver is dropped
+pub fn spawn_account_subscription(ws_url: String, pubkey: Pubkey, sender: Sender<AccountUpdate>) {
+    thread::spawn(move || loop {
+        match PubsubClient::account_subscribe(&ws_url, &pubkey, Some(account_config())) {
+            Ok((_subscription, receiver)) => {
+                for response in receiver.iter() {
+                    if let Some(update) = decode_update(response.context.slot, pubkey, &response.value, true) {
+                        if sender.send(update).is_err() {
+                            return;
+                        }
+                    }
+                }
+                println!("⚠️  Subscription for {} disconnected, reconnecting...", pubkey);
+            }
+            Err(e) => println!("⚠️  accountSubscribe for {} failed: {}, retrying...", pubkey, e),
+        }
+        thread::sleep(RECONNECT_DELAY);
+    });
+}
+
+/// Prints one change as a line of the live feed
+fn print_change(slot: u64, change: &Change, snapshot: &LiveSnapshot, registry: &DecoderRegistry) {
+    let time = Utc::now().format("%H:%M:%S");
+    match change {
+        Change::Created(account) => {
+            println!("\n🆕 [{} slot {}] CREATED {} ({} bytes, {} lamports)",
+                time, slot, account.pubkey, account.data.len(), account.lamports);
+            if let Some(decoded) = registry.decode(&account.data, &snapshot.owner_of(&account.pubkey)) {
+                println!("   type: {}", decoded.type_name);
+            } else if account.data.len() >= 8 {
+                println!("   discriminator: {}", hex::encode(&account.data[..8]));
+            }
+        }
+        Change::Updated(old, new) => {
+            println!("\n✏️  [{} slot {}] UPDATED {}", time, slot, new.pubkey);
+            let keys: HashSet<Pubkey> = snapshot.accounts.keys().copied().collect();
+            let owner = snapshot.owner_of(&new.pubkey);
+            let old_layout = registry.layout(&old.data, &owner, &keys);
+            let new_layout = registry.layout(&new.data, &owner, &keys);
+            diff::print_changes(old, new, &old_layout, &new_layout);
+        }
+        Change::Closed(account) => {
+            println!("\n🗑️  [{} slot {}] CLOSED {} ({} lamports reclaimed)",
+                time, slot, account.pubkey, account.lamports);
+        }
+    }
+}
+
+/// Subscribes to the program and the given accounts, calling `on_change` for every change
+/// to the in-memory snapshot until the subscriptions end
+pub fn run_subscriptions(
+    fetcher: &mut AccountFetcher,
+    filters: Vec<RpcFilterType>,
+    accounts: Vec<Pubkey>,
+    mut on_change: impl FnMut(u64, &Change, &LiveSnapshot) -> Result<()>,
+) -> Result<()> {
+    let mut snapshot = LiveSnapshot::load(fetcher, filters.clone(), &accounts)?;
+    let ws_url = fetcher.ws_url();
+    println!("Tracking {} accounts via {}", snapshot.accounts.len(), ws_url);
+    if !filters.is_empty() {
+        println!("Filters: {:?}", filters);
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    spawn_program_subscription(ws_url.clone(), snapshot.program_id, filters, sender.clone());
+    for pubkey in accounts {
+        println!("Also watching account {}", pubkey);
+        spawn_account_subscription(ws_url.clone(), pubkey, sender.clone());
+    }
+    drop(sender);
+
+    println!("Waiting for changes (Ctrl+C to stop)...");
+    for update in receiver.iter() {
+        let slot = update.slot;
+        if let Some(change) = snapshot.apply(update) {
+            on_change(slot, &change, &snapshot)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Re-fetches the program accounts and the given accounts every `interval`, calling
+/// `on_change` for every difference from the previous fetch. Useful where WebSocket
+/// subscriptions are unavailable
+pub fn run_polling(
+    fetcher: &mut AccountFetcher,
+    filters: Vec<RpcFilterType>,
+    accounts: Vec<Pubkey>,
+    interval: Duration,
+    mut on_change: impl FnMut(u64, &Change, &LiveSnapshot) -> Result<()>,
+) -> Result<()> {
+    let mut snapshot = LiveSnapshot::load(fetcher, filters.clone(), &accounts)?;
+    println!("Tracking {} accounts, polling every {}s (Ctrl+C to stop)...",
+        snapshot.accounts.len(), interval.as_secs());
+
+    loop {
+        thread::sleep(interval);
+
+        let fetched = match fetcher.get_filtered_accounts(filters.clone()) {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                println!("⚠️  Poll failed: {}", e);
+                continue;
+            }
+        };
+        let watched = if accounts.is_empty() {
+            Ok(Vec::new())
+        } else {
+            fetcher.with_retry(|client| Ok(client.get_multiple_accounts(&accounts)?))
+        };
+        let watched = match watched {
+            Ok(watched) => watched,
+            Err(e) => {
+                println!("⚠️  Poll failed: {}", e);
+                continue;
+            }
+        };
+        let (client, _) = fetcher.get_client();
+        let slot = client.get_slot().unwrap_or_default();
+
+        let watched: Vec<(Pubkey, Account)> = accounts.iter().copied()
+            .zip(watched)
+            .filter_map(|(pubkey, account)| Some((pubkey, account?)))
+            .collect();
+        let seen: HashSet<Pubkey> = fetched.iter().chain(&watched).map(|(pubkey, _)| *pubkey).collect();
+        let gone: Vec<Pubkey> = snapshot.accounts.keys().filter(|p| !seen.contains(p)).copied().collect();
+
+        let mut changes = Vec::new();
+        for (pubkey, account) in fetched {
+            changes.extend(snapshot.apply(AccountUpdate { slot, pubkey, account, watched: false }));
+        }
+        for (pubkey, account) in watched {
+            changes.extend(snapshot.apply(AccountUpdate { slot, pubkey, account, watched: true }));
+        }
+        for pubkey in gone {
+            changes.extend(snapshot.remove(&pubkey).map(Change::Closed));
+        }
+
+        for change in &changes {
+            on_change(slot, change, &snapshot)?;
+        }
+    }
+}
+
+/// Subscribes to the program (and optionally specific accounts) and streams a live change feed.
+/// Flags: `--discriminator HEX`, `--memcmp OFFSET:VALUE`, `--size BYTES`, `--account PUBKEY`
+pub fn watch_program(fetcher: &mut AccountFetcher, args: &[String]) -> Result<()> {
+    let filters = filters::from_args(args, &["--account", "--idl", "--decoders"])?;
+    let registry = DecoderRegistry::from_args(args)?;
+    let accounts: Vec<Pubkey> = filters::flag_values(args, "--account")
+        .iter()
+        .map(|a| Pubkey::from_str(a))
+        .collect::<Result<_, _>>()?;
+
+    println!("\n👀 WATCH MODE");
+    println!("============");
+
+    let (mut created, mut updated, mut closed) = (0, 0, 0);
+    run_subscriptions(fetcher, filters, accounts, |slot, change, snapshot| {
+        match change {
+            Change::Created(_) => created += 1,
+            Change::Updated(..) => updated += 1,
+            Change::Closed(_) => closed += 1,
+        }
+        print_change(slot, change, snapshot, &registry);
+        println!("   ({} accounts tracked: {} created, {} updated, {} closed so far)",
+            snapshot.accounts.len(), created, updated, closed);
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_client::rpc_filter::Memcmp;
+
+    fn snapshot(program_id: Pubkey, watched: &[Pubkey]) -> LiveSnapshot {
+        LiveSnapshot {
+            program_id,
+            accounts: HashMap::new(),
+            filters: vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, vec![7]))],
+            watched: watched.iter().copied().collect(),
+            owners: HashMap::new(),
+        }
+    }
+
+    fn update(pubkey: Pubkey, owner: Pubkey, lamports: u64, data: &[u8], watched: bool) -> AccountUpdate {
+        let account = Account { lamports, data: data.to_vec(), owner, executable: false, rent_epoch: 0 };
+        AccountUpdate { slot: 1, pubkey, account, watched }
+    }
+
+    #[test]
+    fn program_accounts_close_on_owner_change() {
+        let program_id = Pubkey::new_unique();
+        let account = Pubkey::new_unique();
+        let mut live = snapshot(program_id, &[]);
+
+        assert!(live.apply(update(account, program_id, 10, &[8], false)).is_none());
+        assert!(matches!(live.apply(update(account, program_id, 10, &[7], false)), Some(Change::Created(_))));
+        assert!(live.apply(update(account, program_id, 10, &[7], false)).is_none());
+        assert!(matches!(live.apply(update(account, Pubkey::default(), 10, &[7], false)), Some(Change::Closed(_))));
+        assert!(live.accounts.is_empty());
+    }
+
+    #[test]
+    fn watched_accounts_of_other_programs() {
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let account = Pubkey::new_unique();
+        let mut live = snapshot(program_id, &[account]);
+
+        assert!(matches!(live.apply(update(account, owner, 10, &[1], true)), Some(Change::Created(_))));
+        assert_eq!(live.owner_of(&account), owner);
+        assert!(matches!(live.apply(update(account, owner, 10, &[2], true)), Some(Change::Updated(..))));
+        assert!(matches!(live.apply(update(account, owner, 0, &[], true)), Some(Change::Closed(_))));
+        assert_eq!(live.owner_of(&account), program_id);
+    }
+}